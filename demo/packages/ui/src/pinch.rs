@@ -1,6 +1,7 @@
-use dioxus::{html::geometry::euclid::Angle, prelude::*};
+use dioxus::prelude::*;
 use dioxus_gestures::{
     state::gestures::pinch::Pinch,
+    transform::GestureTransform,
     use_gestures::{use_gestures, Gestures},
 };
 
@@ -9,18 +10,14 @@ const DRAGGED_TEXT: &str = "Pinching ...";
 
 #[component]
 pub fn PinchExample() -> Element {
-    let mut rotation = use_signal(|| Angle::<f64>::default());
-    let mut scale = use_signal(|| 1.0);
+    let mut transform = use_signal(|| GestureTransform::default().min_scale(0.25).max_scale(4.0));
     let mut text = use_signal(|| UNDRAGGED_TEXT);
 
     let gestures = use_gestures(
         Gestures::default().pinch(
             Pinch::default()
                 .on_start(move |_| text.set(DRAGGED_TEXT))
-                .on_update(move |data| {
-                    scale.set(scale() * data.delta_distance_scale());
-                    rotation.set(rotation() + data.delta_angle());
-                })
+                .on_update(move |data| transform.write().apply_pinch(&data))
                 .on_end(move |_| text.set(UNDRAGGED_TEXT))
                 .on_cancel(move |_| text.set(UNDRAGGED_TEXT)),
         ),
@@ -28,15 +25,22 @@ pub fn PinchExample() -> Element {
 
     rsx! {
         div {
-        class: "target pinch",
-        style: format!("
+        style: "
             user-select: none;
             touch-action: none;
-            position: relative;
-            transform: scale({}) rotate({}rad);
-        ", scale(), rotation().get()),
+            align-self: flex-start;
+            margin: 1em;
+        ",
         ..gestures.event_handlers(),
-            "{text}"
+            div {
+            class: "target pinch",
+            style: format!("
+                margin: 0;
+                transform-origin: 0 0;
+                transform: {};
+            ", transform.read().to_css_matrix()),
+                "{text}"
+            }
         }
     }
 }
@@ -0,0 +1,190 @@
+//! A visual overlay of the pointers and gestures of a target, enabled with the
+//! `debug` feature.
+
+use dioxus::prelude::*;
+
+use crate::{
+    state::{
+        frame::LocalPoint,
+        recognizer::{RecognizerPhase, TrackedPointer},
+    },
+    use_gestures::UseGestures,
+};
+
+const COLORS: [&str; 6] = [
+    "#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#008080",
+];
+
+/// The state of a recognizer of a target.
+#[derive(Clone)]
+pub struct RecognizerSnapshot {
+    pub name: &'static str,
+    pub phase: RecognizerPhase,
+    pub pointers: Vec<TrackedPointer>,
+}
+
+impl UseGestures {
+    pub fn snapshot(&self) -> Vec<RecognizerSnapshot> {
+        let state = self.state.borrow();
+        state
+            .recognizers()
+            .iter()
+            .filter_map(|(name, recognizer)| {
+                let recognizer = recognizer.try_borrow().ok()?;
+                Some(RecognizerSnapshot {
+                    name,
+                    phase: recognizer.phase(),
+                    pointers: recognizer.tracked_pointers(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Draws the pointers tracked by the recognizers of a target, the offset of
+/// single-pointer gestures, the center, distance and angle of two-pointer
+/// gestures, and the phase of every recognizer.
+///
+/// Place it inside the target, which needs to be positioned. Positions are the
+/// local coordinates of the pointers in the frame of their target, see
+/// [`UseGesturesOptions::frame_mapping`].
+///
+/// [`UseGesturesOptions::frame_mapping`]: crate::state::options::UseGesturesOptions::frame_mapping
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_gestures::{
+///     debug::GestureDebugOverlay,
+///     state::gestures::drag::Drag,
+///     use_gestures::{use_gestures, Gestures},
+/// };
+///
+/// #[component]
+/// pub fn DebuggedTarget() -> Element {
+///     let gestures = use_gestures(Gestures::default().drag(Drag::default()));
+///     let handlers = gestures.clone().event_handlers();
+///
+///     rsx! {
+///         div {
+///             style: "position: relative; touch-action: none;",
+///             ..handlers,
+///             GestureDebugOverlay { gestures }
+///         }
+///     }
+/// }
+/// ```
+#[component]
+pub fn GestureDebugOverlay(gestures: UseGestures) -> Element {
+    if let Some(revision) = gestures.state.borrow().revision() {
+        revision.read();
+    }
+    let snapshot = gestures.snapshot();
+
+    let mut pointers: Vec<&TrackedPointer> = Vec::new();
+    for pointer in snapshot.iter().flat_map(|recognizer| &recognizer.pointers) {
+        if !pointers.iter().any(|known| known.id == pointer.id) {
+            pointers.push(pointer);
+        }
+    }
+
+    let phases = snapshot
+        .iter()
+        .map(|recognizer| format!("{}: {:?}", recognizer.name, recognizer.phase))
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    rsx! {
+        svg {
+            style: "
+                position: absolute;
+                inset: 0;
+                width: 100%;
+                height: 100%;
+                overflow: visible;
+                pointer-events: none;
+                font: 11px monospace;
+            ",
+            text { x: 4, y: 14, fill: "black", "{phases}" }
+            for (index , recognizer) in snapshot.iter().enumerate() {
+                if recognizer.phase != RecognizerPhase::Idle && recognizer.name != "hover" {
+                    {gesture(recognizer.name, COLORS[index % COLORS.len()], &recognizer.pointers)}
+                }
+            }
+            for pointer in pointers {
+                {tracked_pointer(pointer)}
+            }
+        }
+    }
+}
+
+fn tracked_pointer(pointer: &TrackedPointer) -> Element {
+    let position = pointer.local_coordinates();
+    let label = format!(
+        "{:?} {} {:.2}",
+        pointer.id,
+        pointer.current_data.pointer_type(),
+        pointer.current_data.pressure()
+    );
+
+    rsx! {
+        circle {
+            cx: position.x,
+            cy: position.y,
+            r: 12,
+            fill: "none",
+            stroke: "black",
+        }
+        text { x: position.x + 16.0, y: position.y - 8.0, fill: "black", "{label}" }
+    }
+}
+
+fn gesture(name: &str, color: &str, pointers: &[TrackedPointer]) -> Element {
+    match pointers {
+        [pointer] => {
+            let initial = pointer.initial_local_coordinates();
+            let current = pointer.local_coordinates();
+            let offset = current - initial;
+            rsx! {
+                line {
+                    x1: initial.x,
+                    y1: initial.y,
+                    x2: current.x,
+                    y2: current.y,
+                    stroke: color,
+                    stroke_width: 2,
+                }
+                text {
+                    x: current.x + 16.0,
+                    y: current.y + 16.0,
+                    fill: color,
+                    "{name} ({offset.x:.1}, {offset.y:.1})"
+                }
+            }
+        }
+        [a, b] => {
+            let a = a.local_coordinates();
+            let b = b.local_coordinates();
+            let center: LocalPoint = a.lerp(b, 0.5);
+            let distance = (b - a).length();
+            let angle = (b - a).angle_from_x_axis().to_degrees();
+            rsx! {
+                line {
+                    x1: a.x,
+                    y1: a.y,
+                    x2: b.x,
+                    y2: b.y,
+                    stroke: color,
+                    stroke_width: 2,
+                }
+                circle { cx: center.x, cy: center.y, r: 4, fill: color }
+                text {
+                    x: center.x + 8.0,
+                    y: center.y - 8.0,
+                    fill: color,
+                    "{name} {distance:.1}px {angle:.1}°"
+                }
+            }
+        }
+        _ => rsx! {},
+    }
+}
@@ -1,6 +1,10 @@
 //! [`Drag`]: crate::state::gestures::drag::Drag
 //! [`Pinch`]: crate::state::gestures::pinch::Pinch
 //! [`Hover`]: crate::state::gestures::hover::Hover
+//! [`Stroke`]: crate::state::gestures::stroke::Stroke
+//! [`Rotate`]: crate::state::gestures::rotate::Rotate
+//! [`Scale`]: crate::state::gestures::scale::Scale
+//! [`ContextMenu`]: crate::state::gestures::context_menu::ContextMenu
 #![doc = include_str!("../README.md")]
 
 pub mod use_gestures;
@@ -8,3 +12,17 @@ pub mod use_gestures;
 pub use use_gestures::use_gestures;
 
 pub mod state;
+
+pub mod transform;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+#[cfg(any(test, feature = "trace"))]
+pub mod trace;
+
+#[cfg(feature = "debug")]
+pub mod debug;
+
+#[cfg(any(test, feature = "viewport"))]
+pub mod viewport;
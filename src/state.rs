@@ -1,5 +1,14 @@
+pub mod arbitration;
+pub mod arena;
+pub mod capture;
+pub mod delivery;
 pub mod events;
 pub mod external_handlers;
+pub mod frame;
 pub mod gestures;
+pub(crate) mod instrument;
+#[allow(clippy::module_inception)]
 pub mod state;
 pub mod options;
+pub mod recognizer;
+pub(crate) mod schedule;
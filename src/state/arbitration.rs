@@ -0,0 +1,325 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::state::{
+    arena::GestureArena,
+    gestures::pointer::PointerId,
+    instrument,
+    recognizer::{GestureRecognizer, NamedRecognizer, RecognizerPhase},
+};
+
+/// Relationships between the recognizers of a target, referenced by the name
+/// they were registered with. The built-in recognizers are named `"hover"`,
+/// `"drag"`, `"pinch"` and, if set, `"stroke"`, `"rotate"`, `"scale"` and
+/// `"context_menu"`.
+///
+/// Recognizers are exclusive by default: while one of them is started, no other
+/// exclusive recognizer may start. `"hover"` is not exclusive, and `"rotate"`
+/// and `"scale"` are simultaneous with `"pinch"`. Drag and pinch never compete,
+/// as they are made of a different number of pointers.
+///
+/// ```rust
+/// use dioxus_gestures::{state::arbitration::Arbitration, use_gestures::Gestures};
+///
+/// let gestures = Gestures::default().arbitration(
+///     Arbitration::default()
+///         .requires_failure_of("tap", "double_tap")
+///         .simultaneous_with("rotate", "pinch"),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct Arbitration {
+    pub simultaneous: Vec<(&'static str, &'static str)>,
+    pub failure_requirements: Vec<(&'static str, &'static str)>,
+    pub non_exclusive: Vec<&'static str>,
+}
+
+impl Arbitration {
+    /// Allows `recognizer` and `other` to be started at the same time.
+    pub fn simultaneous_with(mut self, recognizer: &'static str, other: &'static str) -> Self {
+        self.simultaneous.push((recognizer, other));
+        self
+    }
+
+    /// Holds back `recognizer` until `other` is idle. Once `other` starts,
+    /// `recognizer` is cancelled.
+    pub fn requires_failure_of(mut self, recognizer: &'static str, other: &'static str) -> Self {
+        self.failure_requirements.push((recognizer, other));
+        self
+    }
+
+    pub fn exclusive(mut self, recognizer: &'static str, exclusive: bool) -> Self {
+        self.non_exclusive.retain(|name| *name != recognizer);
+        if !exclusive {
+            self.non_exclusive.push(recognizer);
+        }
+        self
+    }
+
+    fn is_exclusive(&self, recognizer: &str) -> bool {
+        !self.non_exclusive.contains(&recognizer)
+    }
+
+    fn is_simultaneous(&self, a: &str, b: &str) -> bool {
+        self.simultaneous
+            .iter()
+            .any(|&(x, y)| (x == a && y == b) || (x == b && y == a))
+    }
+
+    fn requires_failure(&self, recognizer: &str, other: &str) -> bool {
+        self.failure_requirements
+            .iter()
+            .any(|&(x, y)| x == recognizer && y == other)
+    }
+
+    fn excludes(&self, a: &str, b: &str) -> bool {
+        self.is_exclusive(a) && self.is_exclusive(b) && !self.is_simultaneous(a, b)
+    }
+}
+
+impl Default for Arbitration {
+    fn default() -> Self {
+        Self {
+            simultaneous: vec![("rotate", "pinch"), ("scale", "pinch")],
+            failure_requirements: Vec::new(),
+            non_exclusive: vec!["hover"],
+        }
+    }
+}
+
+pub(crate) struct ArbitrationState {
+    target_id: String,
+    arbitration: Arbitration,
+    arena: Option<GestureArena>,
+    recognizers: Vec<(&'static str, Weak<RefCell<dyn GestureRecognizer>>)>,
+    /// Pointers a recognizer of the target started with, unless they are
+    /// tracked by the arena.
+    claimed: RefCell<Vec<PointerId>>,
+}
+
+impl ArbitrationState {
+    pub(crate) fn new(
+        target_id: String,
+        arbitration: Arbitration,
+        arena: Option<GestureArena>,
+        recognizers: &[NamedRecognizer],
+    ) -> Rc<Self> {
+        Rc::new(Self {
+            target_id,
+            arbitration,
+            arena,
+            recognizers: recognizers
+                .iter()
+                .map(|(name, recognizer)| (*name, Rc::downgrade(recognizer)))
+                .collect(),
+            claimed: RefCell::new(Vec::new()),
+        })
+    }
+
+    pub(crate) fn enter(&self, pointer_id: PointerId) {
+        if let Some(arena) = &self.arena {
+            arena.enter(pointer_id, &self.target_id);
+        }
+    }
+
+    pub(crate) fn leave(&self, pointer_id: PointerId) {
+        match &self.arena {
+            Some(arena) => arena.leave(pointer_id, &self.target_id),
+            None => self.claimed.borrow_mut().retain(|id| *id != pointer_id),
+        }
+    }
+
+    pub(crate) fn has_arena(&self) -> bool {
+        self.arena.is_some()
+    }
+
+    /// Whether a recognizer of the target started with the pointer and still
+    /// holds it.
+    pub(crate) fn is_claimed(&self, pointer_id: PointerId) -> bool {
+        match &self.arena {
+            Some(arena) => arena.is_claimed_by(pointer_id, &self.target_id),
+            None => self.claimed.borrow().contains(&pointer_id),
+        }
+    }
+
+    /// Whether the target may capture a pointer it claimed, see
+    /// [`GestureArena`].
+    pub(crate) fn may_capture(&self, pointer_id: PointerId) -> bool {
+        match &self.arena {
+            Some(arena) => arena.may_capture(pointer_id, &self.target_id),
+            None => self.is_claimed(pointer_id),
+        }
+    }
+
+    /// Tells the arena, if any, whether a recognizer of the target may still
+    /// claim the pointer.
+    pub(crate) fn set_undecided(&self, pointer_id: PointerId, undecided: bool) {
+        if let Some(arena) = &self.arena {
+            arena.set_undecided(pointer_id, &self.target_id, undecided);
+        }
+    }
+
+    fn recognizer(&self, name: &str) -> Weak<RefCell<dyn GestureRecognizer>> {
+        self.recognizers
+            .iter()
+            .find(|(recognizer, _)| *recognizer == name)
+            .map(|(_, recognizer)| Weak::clone(recognizer))
+            .expect("arbiters are only created for registered recognizers")
+    }
+
+    fn others<'a>(&'a self, name: &'a str) -> impl Iterator<Item = NamedRecognizer> + 'a {
+        self.recognizers
+            .iter()
+            .filter(move |(other, _)| *other != name)
+            .filter_map(|(other, recognizer)| Some((*other, recognizer.upgrade()?)))
+    }
+}
+
+/// The handle a recognizer uses to take part in the arbitration of its target.
+#[derive(Clone)]
+pub struct Arbiter {
+    name: &'static str,
+    state: Rc<ArbitrationState>,
+}
+
+impl Arbiter {
+    pub(crate) fn new(name: &'static str, state: &Rc<ArbitrationState>) -> Self {
+        Self {
+            name,
+            state: Rc::clone(state),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Asks whether the recognizer may start now with the given pointers. If it
+    /// may, recognizers that require its failure are cancelled and the pointers
+    /// are claimed in the [`GestureArena`], if any.
+    pub fn try_start(&self, pointers: &[PointerId]) -> bool {
+        let arbitration = &self.state.arbitration;
+        let permitted = self.state.others(self.name).all(|(other, recognizer)| {
+            let Ok(recognizer) = recognizer.try_borrow() else {
+                return true;
+            };
+            match recognizer.phase() {
+                RecognizerPhase::Idle => true,
+                RecognizerPhase::Possible => !arbitration.requires_failure(self.name, other),
+                RecognizerPhase::Started => {
+                    !arbitration.requires_failure(self.name, other)
+                        && !arbitration.excludes(self.name, other)
+                }
+            }
+        });
+
+        let permitted = permitted
+            && self.state.arena.as_ref().is_none_or(|arena| {
+                arena.claim(
+                    &self.state.target_id,
+                    pointers,
+                    self.state.recognizer(self.name),
+                )
+            });
+
+        if permitted {
+            if self.state.arena.is_none() {
+                let mut claimed = self.state.claimed.borrow_mut();
+                for pointer in pointers {
+                    if !claimed.contains(pointer) {
+                        claimed.push(*pointer);
+                    }
+                }
+            }
+
+            self.state
+                .others(self.name)
+                .filter(|(other, _)| arbitration.requires_failure(other, self.name))
+                .for_each(|(_, recognizer)| {
+                    if let Ok(mut recognizer) = recognizer.try_borrow_mut() {
+                        recognizer.cancel();
+                    }
+                });
+        } else {
+            instrument::event!(debug, "start denied by arbitration");
+        }
+
+        permitted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dioxus::html::point_interaction::InteractionLocation;
+
+    use crate::{
+        state::{
+            arbitration::Arbitration,
+            gestures::{down_pointer::DownPointerGestureState, drag::Drag},
+        },
+        testing::{CallbackLog, GestureHarness, PointerSequence, SyntheticPointer},
+        use_gestures::Gestures,
+    };
+
+    fn long_drag(log: &CallbackLog) -> DownPointerGestureState<Drag> {
+        let drag = Drag::default()
+            .has_started(|[initial, current]| {
+                (current.client_coordinates() - initial.client_coordinates()).length() >= 20.0
+            })
+            .on_start(log.record("long_drag.start"))
+            .on_cancel(log.record("long_drag.cancel"));
+        DownPointerGestureState::new(drag)
+    }
+
+    fn drag_by(distance: f64) -> PointerSequence {
+        PointerSequence::default()
+            .down(SyntheticPointer::touch(1))
+            .move_by_steps(1, (distance, 0.0), 10)
+    }
+
+    #[test]
+    fn exclusive_recognizers_do_not_start_together() {
+        let log = CallbackLog::default();
+        let mut harness = GestureHarness::new(
+            Gestures::default()
+                .drag(Drag::default().on_start(log.record("drag.start")))
+                .recognizer("long_drag", long_drag(&log)),
+        );
+
+        harness.play(drag_by(30.0));
+        log.assert_eq(&["drag.start"]);
+    }
+
+    #[test]
+    fn simultaneous_recognizers_start_together() {
+        let log = CallbackLog::default();
+        let mut harness = GestureHarness::new(
+            Gestures::default()
+                .drag(Drag::default().on_start(log.record("drag.start")))
+                .recognizer("long_drag", long_drag(&log))
+                .arbitration(Arbitration::default().simultaneous_with("drag", "long_drag")),
+        );
+
+        harness.play(drag_by(30.0));
+        log.assert_eq(&["drag.start", "long_drag.start"]);
+    }
+
+    #[test]
+    fn waits_for_the_failure_of_another_recognizer() {
+        let log = CallbackLog::default();
+        let mut harness = GestureHarness::new(
+            Gestures::default()
+                .drag(Drag::default().on_start(log.record("drag.start")))
+                .recognizer("long_drag", long_drag(&log))
+                .arbitration(Arbitration::default().requires_failure_of("drag", "long_drag")),
+        );
+
+        harness.play(drag_by(10.0));
+        log.assert_eq(&[]);
+
+        harness.play(PointerSequence::default().move_to(SyntheticPointer::touch(1).at(30.0, 0.0)));
+        log.assert_eq(&["long_drag.start"]);
+    }
+}
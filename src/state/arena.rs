@@ -0,0 +1,277 @@
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::state::{gestures::pointer::PointerId, recognizer::GestureRecognizer};
+
+/// Decides which of several nested gesture targets owns a pointer.
+///
+/// Provide an arena as context above nested targets. Every target that
+/// receives a `pointer_down` enters the arena for that pointer, innermost
+/// first as the event bubbles up. A recognizer that starts claims its pointers;
+/// a claim held by an enclosing target is taken over and the enclosing
+/// recognizer is cancelled, while a claim held by an inner target denies the
+/// start.
+///
+/// Targets in an arena capture a pointer only once they claim it and no inner
+/// target may still claim it, as inner targets get no more events of a
+/// pointer an enclosing target captured. A target whose claim is taken over
+/// releases its capture.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_gestures::{
+///     state::{arena::GestureArena, gestures::drag::Drag},
+///     use_gestures::{use_gestures, Gestures},
+/// };
+///
+/// #[component]
+/// pub fn Canvas() -> Element {
+///     use_context_provider(GestureArena::default);
+///     let canvas = use_gestures(Gestures::default().drag(Drag::default()));
+///     let card = use_gestures(Gestures::default().drag(Drag::default()));
+///
+///     rsx! {
+///         div {
+///             ..canvas.event_handlers(),
+///             div {
+///                 ..card.event_handlers(),
+///                 "Card"
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Default)]
+pub struct GestureArena {
+    pointers: Rc<RefCell<Vec<ArenaPointer>>>,
+}
+
+struct ArenaPointer {
+    id: PointerId,
+    /// Targets that received the pointer, innermost first.
+    contenders: Vec<Contender>,
+    claim: Option<Claim>,
+}
+
+struct Contender {
+    target_id: String,
+    /// Whether a recognizer of the target may still claim the pointer.
+    undecided: bool,
+}
+
+struct Claim {
+    target_id: String,
+    recognizer: Weak<RefCell<dyn GestureRecognizer>>,
+}
+
+impl ArenaPointer {
+    fn depth(&self, target_id: &str) -> usize {
+        self.contenders
+            .iter()
+            .position(|contender| contender.target_id == target_id)
+            .unwrap_or(usize::MAX)
+    }
+}
+
+impl GestureArena {
+    pub(crate) fn enter(&self, pointer_id: PointerId, target_id: &str) {
+        let mut pointers = self.pointers.borrow_mut();
+        let index = match pointers.iter().position(|pointer| pointer.id == pointer_id) {
+            Some(index) => index,
+            None => {
+                pointers.push(ArenaPointer {
+                    id: pointer_id,
+                    contenders: Vec::new(),
+                    claim: None,
+                });
+                pointers.len() - 1
+            }
+        };
+
+        let pointer = &mut pointers[index];
+        if !pointer
+            .contenders
+            .iter()
+            .any(|contender| contender.target_id == target_id)
+        {
+            pointer.contenders.push(Contender {
+                target_id: target_id.to_string(),
+                undecided: true,
+            });
+        }
+    }
+
+    pub(crate) fn leave(&self, pointer_id: PointerId, target_id: &str) {
+        let mut pointers = self.pointers.borrow_mut();
+        if let Some(pointer) = pointers.iter_mut().find(|pointer| pointer.id == pointer_id) {
+            pointer
+                .contenders
+                .retain(|contender| contender.target_id != target_id);
+            if pointer
+                .claim
+                .as_ref()
+                .is_some_and(|claim| claim.target_id == target_id)
+            {
+                pointer.claim = None;
+            }
+        }
+        pointers.retain(|pointer| !pointer.contenders.is_empty());
+    }
+
+    pub(crate) fn is_claimed_by(&self, pointer_id: PointerId, target_id: &str) -> bool {
+        self.pointers
+            .borrow()
+            .iter()
+            .find(|pointer| pointer.id == pointer_id)
+            .and_then(|pointer| pointer.claim.as_ref())
+            .is_some_and(|claim| claim.target_id == target_id)
+    }
+
+    pub(crate) fn set_undecided(&self, pointer_id: PointerId, target_id: &str, undecided: bool) {
+        let mut pointers = self.pointers.borrow_mut();
+        let contender = pointers
+            .iter_mut()
+            .filter(|pointer| pointer.id == pointer_id)
+            .flat_map(|pointer| &mut pointer.contenders)
+            .find(|contender| contender.target_id == target_id);
+        if let Some(contender) = contender {
+            contender.undecided = undecided;
+        }
+    }
+
+    /// Whether `target_id` holds the claim on the pointer and no target inside
+    /// it may still take the claim over.
+    pub(crate) fn may_capture(&self, pointer_id: PointerId, target_id: &str) -> bool {
+        let pointers = self.pointers.borrow();
+        let Some(pointer) = pointers.iter().find(|pointer| pointer.id == pointer_id) else {
+            return false;
+        };
+        let is_claimed = pointer
+            .claim
+            .as_ref()
+            .is_some_and(|claim| claim.target_id == target_id);
+        let depth = pointer.depth(target_id);
+        is_claimed
+            && !pointer.contenders[..depth.min(pointer.contenders.len())]
+                .iter()
+                .any(|contender| contender.undecided)
+    }
+
+    /// Claims `pointer_ids` for a recognizer of `target_id`. Recognizers of
+    /// enclosing targets that held one of the pointers are cancelled.
+    pub(crate) fn claim(
+        &self,
+        target_id: &str,
+        pointer_ids: &[PointerId],
+        recognizer: Weak<RefCell<dyn GestureRecognizer>>,
+    ) -> bool {
+        let superseded = {
+            let mut pointers = self.pointers.borrow_mut();
+            let mut claimed = pointers
+                .iter_mut()
+                .filter(|pointer| pointer_ids.contains(&pointer.id))
+                .collect::<Vec<_>>();
+
+            let is_denied = claimed.iter().any(|pointer| {
+                pointer.claim.as_ref().is_some_and(|claim| {
+                    claim.target_id != target_id
+                        && pointer.depth(&claim.target_id) < pointer.depth(target_id)
+                })
+            });
+            if is_denied {
+                return false;
+            }
+
+            claimed
+                .iter_mut()
+                .filter_map(|pointer| {
+                    pointer.claim.replace(Claim {
+                        target_id: target_id.to_string(),
+                        recognizer: Weak::clone(&recognizer),
+                    })
+                })
+                .filter(|claim| claim.target_id != target_id)
+                .collect::<Vec<_>>()
+        };
+
+        for claim in superseded {
+            if let Some(recognizer) = claim.recognizer.upgrade() {
+                if let Ok(mut recognizer) = recognizer.try_borrow_mut() {
+                    recognizer.cancel();
+                }
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dioxus::html::point_interaction::InteractionLocation;
+
+    use crate::{
+        state::{arena::GestureArena, gestures::drag::Drag},
+        testing::{CallbackLog, GestureHarness, PointerSequence, SyntheticPointer},
+        use_gestures::Gestures,
+    };
+
+    #[test]
+    fn inner_target_takes_over_the_pointer() {
+        let log = CallbackLog::default();
+        let arena = GestureArena::default();
+        let mut card = GestureHarness::new(
+            Gestures::default().arena(Some(arena.clone())).drag(
+                Drag::default()
+                    .on_start(log.record("card.start"))
+                    .on_end(log.record("card.end")),
+            ),
+        );
+        let mut canvas = GestureHarness::new(
+            Gestures::default().arena(Some(arena)).drag(
+                Drag::default()
+                    .has_started(|[initial, current]| {
+                        (current.client_coordinates() - initial.client_coordinates()).length()
+                            >= 2.0
+                    })
+                    .on_start(log.record("canvas.start"))
+                    .on_cancel(log.record("canvas.cancel")),
+            ),
+        );
+
+        let sequence = PointerSequence::default()
+            .down(SyntheticPointer::touch(1))
+            .move_by_steps(1, (9.0, 0.0), 3)
+            .up(1);
+        let mut card_captured = false;
+        for (kind, pointer) in &sequence.events {
+            // Events of the pointer bubble from the card up to the canvas, or
+            // only reach the canvas once it captured the pointer.
+            if canvas.captured_pointers().is_empty() {
+                card.send(*kind, pointer);
+            }
+            canvas.send(*kind, pointer);
+            card_captured |= !card.captured_pointers().is_empty();
+            assert!(
+                canvas.captured_pointers().is_empty(),
+                "the canvas captured the pointer while the card could still claim it",
+            );
+        }
+
+        log.assert_eq(&["canvas.start", "canvas.cancel", "card.start", "card.end"]);
+        assert!(card_captured);
+    }
+
+    #[test]
+    fn target_captures_once_no_inner_target_contends() {
+        let arena = GestureArena::default();
+        let mut canvas = GestureHarness::new(Gestures::default().arena(Some(arena)));
+
+        canvas.play(PointerSequence::default().down(SyntheticPointer::touch(1)));
+        assert!(canvas.captured_pointers().is_empty());
+        canvas.play(PointerSequence::default().move_to(SyntheticPointer::touch(1).at(20.0, 0.0)));
+        assert_eq!(canvas.captured_pointers(), [1]);
+    }
+}
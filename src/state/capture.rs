@@ -0,0 +1,118 @@
+use std::fmt;
+
+/// Why a pointer could not be captured or released by the target element.
+///
+/// Only reported with the `web` feature, see
+/// [`UseGesturesOptions::on_pointer_capture_error`].
+///
+/// [`UseGesturesOptions::on_pointer_capture_error`]: crate::state::options::UseGesturesOptions::on_pointer_capture_error
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PointerCaptureError {
+    /// The target has not been mounted yet, or its renderer does not provide a
+    /// `web_sys::Element`.
+    NotMounted { pointer_id: i32 },
+    /// The browser rejected the call, usually because the pointer is no longer
+    /// active.
+    Rejected { pointer_id: i32, message: String },
+}
+
+impl PointerCaptureError {
+    pub fn pointer_id(&self) -> i32 {
+        match self {
+            Self::NotMounted { pointer_id } | Self::Rejected { pointer_id, .. } => *pointer_id,
+        }
+    }
+}
+
+#[cfg(feature = "web")]
+impl PointerCaptureError {
+    pub(crate) fn rejected(pointer_id: i32, error: web_sys::wasm_bindgen::JsValue) -> Self {
+        use web_sys::wasm_bindgen::JsCast;
+
+        let message = error
+            .dyn_ref::<web_sys::DomException>()
+            .map(web_sys::DomException::message)
+            .or_else(|| error.as_string())
+            .unwrap_or_else(|| format!("{error:?}"));
+        Self::Rejected {
+            pointer_id,
+            message,
+        }
+    }
+}
+
+impl fmt::Display for PointerCaptureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotMounted { pointer_id } => {
+                write!(
+                    f,
+                    "pointer capture of {pointer_id} failed: target is not mounted"
+                )
+            }
+            Self::Rejected {
+                pointer_id,
+                message,
+            } => write!(f, "pointer capture of {pointer_id} failed: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for PointerCaptureError {}
+
+/// When a target captures a pointer, set with
+/// [`UseGesturesOptions::capture_policy`]. A captured pointer keeps sending its
+/// events to the target after it left the element, but no longer reaches the
+/// rest of the page.
+///
+/// ```rust
+/// use dioxus_gestures::state::{
+///     capture::{CaptureMode, CapturePolicy},
+///     options::UseGesturesOptions,
+/// };
+///
+/// let options = UseGesturesOptions::default().capture_policy(CapturePolicy::PerPointerType {
+///     mouse: CaptureMode::OnGestureStart,
+///     pen: CaptureMode::Always,
+///     touch: CaptureMode::Always,
+/// });
+/// ```
+///
+/// [`UseGesturesOptions::capture_policy`]: crate::state::options::UseGesturesOptions::capture_policy
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CapturePolicy {
+    Never,
+    /// Captures on `pointer_down`.
+    #[default]
+    Always,
+    /// Captures once a recognizer like drag or pinch starts with the pointer.
+    OnGestureStart,
+    /// Chooses by `pointer_type`. Pointers of an unknown type use `mouse`.
+    PerPointerType {
+        mouse: CaptureMode,
+        pen: CaptureMode,
+        touch: CaptureMode,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaptureMode {
+    Never,
+    Always,
+    OnGestureStart,
+}
+
+impl CapturePolicy {
+    pub fn mode(&self, pointer_type: &str) -> CaptureMode {
+        match *self {
+            Self::Never => CaptureMode::Never,
+            Self::Always => CaptureMode::Always,
+            Self::OnGestureStart => CaptureMode::OnGestureStart,
+            Self::PerPointerType { mouse, pen, touch } => match pointer_type {
+                "pen" => pen,
+                "touch" => touch,
+                _ => mouse,
+            },
+        }
+    }
+}
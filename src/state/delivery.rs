@@ -0,0 +1,16 @@
+/// When the updates of down pointer gestures like [`Drag`], [`Pinch`] and
+/// [`Stroke`] are delivered.
+///
+/// [`Drag`]: crate::state::gestures::drag::Drag
+/// [`Pinch`]: crate::state::gestures::pinch::Pinch
+/// [`Stroke`]: crate::state::gestures::stroke::Stroke
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UpdateDelivery {
+    /// One update for every pointer move.
+    #[default]
+    Immediate,
+    /// At most one update per animation frame, combining the movement of all
+    /// pointers since the preceding update. Pending updates are delivered
+    /// before a gesture ends or is cancelled.
+    PerFrame,
+}
@@ -1,3 +1,5 @@
+use dioxus::{html::PointerData, prelude::Event};
+
 pub trait PointerEventReceiver<E> {
     fn pointer_over(&mut self, event: E);
 
@@ -14,4 +16,51 @@ pub trait PointerEventReceiver<E> {
     fn pointer_out(&mut self, event: E);
 
     fn pointer_leave(&mut self, event: E);
+
+    fn got_pointer_capture(&mut self, _event: E) {}
+
+    /// The pointer is no longer captured, either after it was released or
+    /// because the browser revoked the capture.
+    fn lost_pointer_capture(&mut self, _event: E) {}
+}
+
+/// The pointer events a target listens to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    any(test, feature = "trace"),
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum PointerEventKind {
+    Over,
+    Enter,
+    Down,
+    Move,
+    Up,
+    Cancel,
+    Out,
+    Leave,
+    GotCapture,
+    LostCapture,
+}
+
+impl PointerEventKind {
+    pub fn dispatch<R: PointerEventReceiver<Event<PointerData>> + ?Sized>(
+        self,
+        receiver: &mut R,
+        event: Event<PointerData>,
+    ) {
+        match self {
+            Self::Over => receiver.pointer_over(event),
+            Self::Enter => receiver.pointer_enter(event),
+            Self::Down => receiver.pointer_down(event),
+            Self::Move => receiver.pointer_move(event),
+            Self::Up => receiver.pointer_up(event),
+            Self::Cancel => receiver.pointer_cancel(event),
+            Self::Out => receiver.pointer_out(event),
+            Self::Leave => receiver.pointer_leave(event),
+            Self::GotCapture => receiver.got_pointer_capture(event),
+            Self::LostCapture => receiver.lost_pointer_capture(event),
+        }
+    }
 }
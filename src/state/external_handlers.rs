@@ -12,6 +12,8 @@ pub struct ExternalHandlers {
     pub on_pointer_cancel: Option<EventHandler<Event<PointerData>>>,
     pub on_pointer_out: Option<EventHandler<Event<PointerData>>>,
     pub on_pointer_leave: Option<EventHandler<Event<PointerData>>>,
+    pub on_got_pointer_capture: Option<EventHandler<Event<PointerData>>>,
+    pub on_lost_pointer_capture: Option<EventHandler<Event<PointerData>>>,
 }
 
 impl ExternalHandlers {
@@ -41,6 +43,12 @@ impl ExternalHandlers {
             on_pointer_leave: Some(EventHandler::new(|e: Event<PointerData>| {
                 e.stop_propagation()
             })),
+            on_got_pointer_capture: Some(EventHandler::new(|e: Event<PointerData>| {
+                e.stop_propagation()
+            })),
+            on_lost_pointer_capture: Some(EventHandler::new(|e: Event<PointerData>| {
+                e.stop_propagation()
+            })),
         }
     }
 
@@ -83,6 +91,22 @@ impl ExternalHandlers {
         self.on_pointer_leave = Some(EventHandler::new(handler));
         self
     }
+
+    pub fn on_got_pointer_capture(
+        mut self,
+        handler: impl FnMut(Event<PointerData>) + 'static,
+    ) -> Self {
+        self.on_got_pointer_capture = Some(EventHandler::new(handler));
+        self
+    }
+
+    pub fn on_lost_pointer_capture(
+        mut self,
+        handler: impl FnMut(Event<PointerData>) + 'static,
+    ) -> Self {
+        self.on_lost_pointer_capture = Some(EventHandler::new(handler));
+        self
+    }
 }
 
 impl PointerEventReceiver<Event<PointerData>> for ExternalHandlers {
@@ -117,4 +141,14 @@ impl PointerEventReceiver<Event<PointerData>> for ExternalHandlers {
     fn pointer_leave(&mut self, event: Event<PointerData>) {
         self.on_pointer_leave.inspect(|handler| handler(event));
     }
+
+    fn got_pointer_capture(&mut self, event: Event<PointerData>) {
+        self.on_got_pointer_capture
+            .inspect(|handler| handler(event));
+    }
+
+    fn lost_pointer_capture(&mut self, event: Event<PointerData>) {
+        self.on_lost_pointer_capture
+            .inspect(|handler| handler(event));
+    }
 }
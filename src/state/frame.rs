@@ -0,0 +1,418 @@
+#[cfg(any(test, feature = "testing"))]
+use std::cell::Cell;
+use std::{cell::RefCell, rc::Rc};
+
+use dioxus::html::{
+    geometry::{
+        euclid::{Point2D, Rect, Size2D, Transform2D, Vector2D},
+        ClientPoint, ClientSpace,
+    },
+    point_interaction::{InteractionElementOffset, InteractionLocation},
+    PointerData,
+};
+
+/// Coordinate space relative to the target, as it was measured when a gesture
+/// started.
+pub struct LocalSpace;
+/// A point in LocalSpace
+pub type LocalPoint = Point2D<f64, LocalSpace>;
+/// A vector in LocalSpace
+pub type LocalVector = Vector2D<f64, LocalSpace>;
+
+/// The target of a gesture, measured when the gesture started and kept until
+/// it ends. Local coordinates stay consistent under pointer capture and for
+/// events fired by child elements, and do not shift when the target moves
+/// during the gesture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TargetFrame {
+    /// The bounding rect of the target in client coordinates.
+    pub rect: Rect<f64, ClientSpace>,
+    pub to_local: Transform2D<f64, ClientSpace, LocalSpace>,
+}
+
+impl TargetFrame {
+    pub fn from_rect(rect: Rect<f64, ClientSpace>) -> Self {
+        Self {
+            rect,
+            to_local: Transform2D::translation(-rect.origin.x, -rect.origin.y),
+        }
+    }
+
+    /// The frame of the target an event was fired on. Its size is unknown, and
+    /// its origin only matches the target if the event was fired by the target
+    /// itself rather than a child element.
+    ///
+    /// Without the `web` feature, every frame is derived this way, so local
+    /// coordinates come from whichever element fired the event that started
+    /// the gesture, and the [`FrameMapping`] of the target is not applied.
+    pub fn from_event(data: &PointerData) -> Self {
+        let origin = data.client_coordinates() - data.element_coordinates().to_vector().cast_unit();
+        Self::from_rect(Rect::new(origin, Size2D::zero()))
+    }
+
+    /// The frame of a target with CSS transforms. `linear` is the linear part of
+    /// the transforms that apply to it, which is all that is needed, as the
+    /// center of an affinely transformed box is the center of its bounding
+    /// rect. Local coordinates are in the untransformed box of the given size.
+    /// Returns `None` if the transform cannot be inverted.
+    pub fn transformed(
+        rect: Rect<f64, ClientSpace>,
+        size: Size2D<f64, LocalSpace>,
+        linear: Transform2D<f64, LocalSpace, ClientSpace>,
+    ) -> Option<Self> {
+        let center = rect.center();
+        let to_local = Transform2D::translation(-center.x, -center.y)
+            .then(&linear.inverse()?)
+            .then_translate((size / 2.0).to_vector());
+        Some(Self { rect, to_local })
+    }
+
+    /// The frame of an SVG element, whose local coordinates are in the user
+    /// space `to_client` maps from, like the matrix of `getScreenCTM()`.
+    /// Returns `None` if the matrix cannot be inverted.
+    pub fn user_space(
+        rect: Rect<f64, ClientSpace>,
+        to_client: Transform2D<f64, LocalSpace, ClientSpace>,
+    ) -> Option<Self> {
+        Some(Self {
+            rect,
+            to_local: to_client.inverse()?,
+        })
+    }
+
+    pub fn local_point(&self, point: ClientPoint) -> LocalPoint {
+        self.to_local.transform_point(point)
+    }
+
+    pub fn local_vector(&self, vector: Vector2D<f64, ClientSpace>) -> LocalVector {
+        self.to_local.transform_vector(vector)
+    }
+}
+
+/// How local coordinates relate to the target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FrameMapping {
+    /// Client coordinates relative to the bounding rect of the target.
+    #[default]
+    BoundingRect,
+    /// Coordinates in the untransformed box of the target, with the CSS
+    /// transforms of the target and its ancestors inverted, as for content of
+    /// a scaled or rotated target.
+    Untransformed,
+    /// Like [`FrameMapping::Untransformed`], but only the transforms of the
+    /// ancestors are inverted. Local vectors are in the space the target is
+    /// positioned in, as for moving a target with `left` and `top` or a
+    /// translation before its own scale and rotation.
+    UntransformedParent,
+    /// The user space of an SVG target, in the units of the `viewBox` and with
+    /// the `transform` attributes of the target and its ancestors inverted,
+    /// through `getScreenCTM()`. Targets that are not SVG graphics elements
+    /// fall back to [`FrameMapping::BoundingRect`].
+    SvgUserSpace,
+    /// The user space of the parent of an SVG target, in which dragging the
+    /// target by its `x` and `y` or a `translate()` moves it along.
+    SvgParentUserSpace,
+}
+
+impl Default for TargetFrame {
+    fn default() -> Self {
+        Self::from_rect(Rect::zero())
+    }
+}
+
+/// The frames measured during an event, by mapping.
+type Measurements = Vec<(FrameMapping, Rc<TargetFrame>)>;
+
+/// Measures the target of a recognizer when one of its gestures starts.
+///
+/// With the `web` feature, the bounding rect of the mounted target is measured.
+/// Otherwise, and before the target is mounted, the frame is derived from the
+/// event that starts the gesture, see [`TargetFrame::from_event`].
+///
+/// The [`FrameMapping`] is applied with the `web` feature only.
+///
+/// The clones of a source share their measurements, so the target is measured
+/// at most once per event and mapping however many recognizers start a gesture
+/// with it.
+#[derive(Clone, Default)]
+pub struct FrameSource {
+    #[cfg(feature = "web")]
+    element: Rc<RefCell<Option<web_sys::Element>>>,
+    mapping: FrameMapping,
+    measured: Rc<RefCell<Measurements>>,
+    /// The client rect a [`GestureHarness`] measures the target with.
+    ///
+    /// [`GestureHarness`]: crate::testing::GestureHarness
+    #[cfg(any(test, feature = "testing"))]
+    rect: Rc<Cell<Option<Rect<f64, ClientSpace>>>>,
+}
+
+impl FrameSource {
+    #[cfg(feature = "web")]
+    pub(crate) fn new(
+        element: &Rc<RefCell<Option<web_sys::Element>>>,
+        mapping: FrameMapping,
+    ) -> Self {
+        Self {
+            element: Rc::clone(element),
+            mapping,
+            measured: Rc::default(),
+            #[cfg(any(test, feature = "testing"))]
+            rect: Rc::default(),
+        }
+    }
+
+    /// The frame of the target during the current event, measured on the first
+    /// call.
+    pub fn measure(&self, data: &PointerData) -> Rc<TargetFrame> {
+        self.measure_with(data, self.mapping)
+    }
+
+    /// Like [`Self::measure`], with a mapping other than the one of the target.
+    pub fn measure_with(&self, data: &PointerData, mapping: FrameMapping) -> Rc<TargetFrame> {
+        let mut measured = self.measured.borrow_mut();
+        if let Some((_, frame)) = measured.iter().find(|(other, _)| *other == mapping) {
+            return Rc::clone(frame);
+        }
+        let frame = Rc::new(self.measure_now(data, mapping));
+        measured.push((mapping, Rc::clone(&frame)));
+        frame
+    }
+
+    /// Forgets the measurements of the preceding event.
+    pub(crate) fn next_event(&self) {
+        self.measured.borrow_mut().clear();
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn set_rect(&self, rect: Rect<f64, ClientSpace>) {
+        self.rect.set(Some(rect));
+    }
+
+    #[cfg_attr(not(feature = "web"), allow(unused_variables))]
+    fn measure_now(&self, data: &PointerData, mapping: FrameMapping) -> TargetFrame {
+        #[cfg(any(test, feature = "testing"))]
+        if let Some(rect) = self.rect.get() {
+            return TargetFrame::from_rect(rect);
+        }
+        #[cfg(feature = "web")]
+        if let Some(element) = &*self.element.borrow() {
+            return web::measure(element, mapping);
+        }
+
+        TargetFrame::from_event(data)
+    }
+}
+
+#[cfg(feature = "web")]
+impl TargetFrame {
+    /// The frame of a mounted element, as the target of a gesture is measured
+    /// with the `web` feature.
+    pub fn measure(element: &web_sys::Element, mapping: FrameMapping) -> Self {
+        web::measure(element, mapping)
+    }
+}
+
+/// The linear part of a computed CSS `transform` value, which is `none`, a
+/// `matrix()` or a `matrix3d()`.
+pub fn parse_css_matrix<Src, Dst>(value: &str) -> Option<Transform2D<f64, Src, Dst>> {
+    let value = value.trim();
+    if value == "none" {
+        return Some(Transform2D::identity());
+    }
+
+    let (name, arguments) = value.strip_suffix(')')?.split_once('(')?;
+    let values = arguments
+        .split(',')
+        .map(|value| value.trim().parse::<f64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match (name.trim(), values.as_slice()) {
+        ("matrix", [a, b, c, d, _, _]) => Some(Transform2D::new(*a, *b, *c, *d, 0.0, 0.0)),
+        ("matrix3d", [a, b, _, _, c, d, ..]) if values.len() == 16 => {
+            Some(Transform2D::new(*a, *b, *c, *d, 0.0, 0.0))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(feature = "web")]
+mod web {
+    use dioxus::html::geometry::{
+        euclid::{Point2D, Rect, Size2D, Transform2D},
+        ClientSpace,
+    };
+    use web_sys::wasm_bindgen::JsCast;
+
+    use super::{parse_css_matrix, FrameMapping, LocalSpace, TargetFrame};
+
+    pub(super) fn measure(element: &web_sys::Element, mapping: FrameMapping) -> TargetFrame {
+        let bounds = element.get_bounding_client_rect();
+        let rect = Rect::new(
+            Point2D::new(bounds.left(), bounds.top()),
+            Size2D::new(bounds.width(), bounds.height()),
+        );
+
+        let ancestor = match mapping {
+            FrameMapping::BoundingRect => return TargetFrame::from_rect(rect),
+            FrameMapping::Untransformed => Some(element.clone()),
+            FrameMapping::UntransformedParent => element.parent_element(),
+            FrameMapping::SvgUserSpace => {
+                return user_space(rect, Some(element.clone()));
+            }
+            FrameMapping::SvgParentUserSpace => {
+                return user_space(rect, element.parent_element());
+            }
+        };
+        let size = match element.dyn_ref::<web_sys::HtmlElement>() {
+            Some(element) => Size2D::new(element.offset_width(), element.offset_height()).to_f64(),
+            None => rect.size.cast_unit(),
+        };
+        linear_transform(ancestor)
+            .and_then(|linear| TargetFrame::transformed(rect, size, linear))
+            .unwrap_or_else(|| TargetFrame::from_rect(rect))
+    }
+
+    fn user_space(rect: Rect<f64, ClientSpace>, element: Option<web_sys::Element>) -> TargetFrame {
+        element
+            .as_ref()
+            .and_then(|element| element.dyn_ref::<web_sys::SvgGraphicsElement>())
+            .and_then(|element| element.get_screen_ctm())
+            .and_then(|ctm| {
+                let to_client =
+                    Transform2D::new(ctm.a(), ctm.b(), ctm.c(), ctm.d(), ctm.e(), ctm.f());
+                TargetFrame::user_space(rect, to_client.cast())
+            })
+            .unwrap_or_else(|| TargetFrame::from_rect(rect))
+    }
+
+    /// The combined linear part of the transforms of an element and its
+    /// ancestors.
+    fn linear_transform(
+        mut element: Option<web_sys::Element>,
+    ) -> Option<Transform2D<f64, LocalSpace, ClientSpace>> {
+        let window = web_sys::window()?;
+        let mut linear = Transform2D::identity();
+        while let Some(current) = element {
+            let style = window.get_computed_style(&current).ok()??;
+            let transform = style.get_property_value("transform").ok()?;
+            linear = linear.then(&parse_css_matrix(&transform)?);
+            element = current.parent_element();
+        }
+        Some(linear)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use dioxus::html::geometry::{
+        euclid::{Angle, Point2D, Rect, Size2D, Transform2D, Vector2D},
+        ClientSpace,
+    };
+
+    use crate::{
+        state::frame::{
+            parse_css_matrix, FrameMapping, FrameSource, LocalPoint, LocalSpace, TargetFrame,
+        },
+        testing::SyntheticPointer,
+    };
+
+    fn assert_near(a: LocalPoint, b: LocalPoint) {
+        assert!((a - b).length() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn measures_once_per_event() {
+        let frames = FrameSource::default();
+        let down = SyntheticPointer::touch(1).at(10.0, 10.0).to_event();
+        let frame = frames.measure(&down.data());
+        assert!(Rc::ptr_eq(&frame, &frames.clone().measure(&down.data())));
+        let svg = frames.measure_with(&down.data(), FrameMapping::SvgUserSpace);
+        assert!(!Rc::ptr_eq(&frame, &svg));
+        assert!(Rc::ptr_eq(
+            &svg,
+            &frames.measure_with(&down.data(), FrameMapping::SvgUserSpace)
+        ));
+
+        frames.next_event();
+        let other = SyntheticPointer::touch(1)
+            .at(30.0, 30.0)
+            .element_at(5.0, 5.0)
+            .to_event();
+        let next = frames.measure(&other.data());
+        assert!(!Rc::ptr_eq(&frame, &next));
+        assert_eq!(next.rect.origin, Point2D::new(25.0, 25.0));
+    }
+
+    #[test]
+    fn parses_computed_transforms() {
+        let rotation = Transform2D::<f64, LocalSpace, ClientSpace>::rotation(Angle::degrees(30.0));
+        let (sin, cos) = (0.5, 3f64.sqrt() / 2.0);
+
+        assert_eq!(
+            parse_css_matrix("none"),
+            Some(Transform2D::<f64, LocalSpace, ClientSpace>::identity())
+        );
+        let matrix = parse_css_matrix::<LocalSpace, ClientSpace>(&format!(
+            "matrix({cos}, {sin}, {}, {cos}, 10, 20)",
+            -sin
+        ))
+        .unwrap();
+        assert!(matrix.approx_eq(&rotation));
+        let matrix3d = parse_css_matrix::<LocalSpace, ClientSpace>(&format!(
+            "matrix3d({cos}, {sin}, 0, 0, {}, {cos}, 0, 0, 0, 0, 1, 0, 10, 20, 0, 1)",
+            -sin
+        ))
+        .unwrap();
+        assert!(matrix3d.approx_eq(&rotation));
+        assert_eq!(
+            parse_css_matrix::<LocalSpace, ClientSpace>("matrix(1, 0)"),
+            None
+        );
+    }
+
+    #[test]
+    fn maps_into_the_untransformed_box() {
+        // A 100x50 box, scaled by 2 and rotated by a quarter turn around its
+        // center at (200, 200), covers 100x200 pixels.
+        let linear = Transform2D::scale(2.0, 2.0).then_rotate(Angle::degrees(90.0));
+        let rect = Rect::new(Point2D::new(150.0, 100.0), Size2D::new(100.0, 200.0));
+        let frame = TargetFrame::transformed(rect, Size2D::new(100.0, 50.0), linear).unwrap();
+
+        assert_near(
+            frame.local_point(Point2D::new(200.0, 200.0)),
+            LocalPoint::new(50.0, 25.0),
+        );
+        let corner =
+            Point2D::new(200.0, 200.0) + linear.transform_vector(Vector2D::new(-50.0, -25.0));
+        assert_near(frame.local_point(corner), LocalPoint::zero());
+        assert_near(
+            frame.local_vector(Vector2D::new(0.0, 20.0)).to_point(),
+            LocalPoint::new(10.0, 0.0),
+        );
+
+        let flat = Transform2D::scale(1.0, 0.0);
+        assert_eq!(
+            TargetFrame::transformed(rect, Size2D::new(100.0, 50.0), flat),
+            None
+        );
+    }
+
+    #[test]
+    fn maps_into_svg_user_space() {
+        // A viewBox zoomed by 2 and placed at (10, 20) in the viewport.
+        let to_client = Transform2D::scale(2.0, 2.0).then_translate(Vector2D::new(10.0, 20.0));
+        let rect = Rect::new(Point2D::new(10.0, 20.0), Size2D::new(200.0, 200.0));
+        let frame = TargetFrame::user_space(rect, to_client).unwrap();
+
+        assert_near(
+            frame.local_point(Point2D::new(30.0, 60.0)),
+            LocalPoint::new(10.0, 20.0),
+        );
+        assert_near(
+            frame.local_vector(Vector2D::new(8.0, -4.0)).to_point(),
+            LocalPoint::new(4.0, -2.0),
+        );
+    }
+}
@@ -1,5 +1,24 @@
+use std::{cell::RefCell, rc::Rc};
+
+use dioxus::html::PointerData;
+
+pub mod context_menu;
 pub mod down_pointer;
 pub mod drag;
 pub mod hover;
 pub mod pinch;
 pub mod pointer;
+pub mod rotate;
+pub mod scale;
+pub mod stroke;
+
+/// A callback of a gesture, shared by the clones of its configuration.
+pub type Handler<T> = Rc<RefCell<dyn FnMut(T)>>;
+
+/// Whether a gesture of one pointer has started, from the initial and the
+/// current data of the pointer.
+pub type StartPredicate = Rc<dyn Fn([&PointerData; 2]) -> bool>;
+
+/// Whether a gesture of two pointers has started, from the initial and the
+/// current data of each pointer.
+pub type PairStartPredicate = Rc<dyn Fn([&PointerData; 2], [&PointerData; 2]) -> bool>;
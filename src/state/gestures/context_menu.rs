@@ -0,0 +1,500 @@
+use std::{cell::RefCell, rc::Rc};
+
+use dioxus::{
+    html::{
+        geometry::ClientPoint,
+        input_data::MouseButton,
+        point_interaction::{InteractionLocation, PointerInteraction},
+        PointerData,
+    },
+    prelude::Event,
+};
+
+use crate::state::{
+    arbitration::Arbiter,
+    events::PointerEventReceiver,
+    frame::{FrameSource, LocalPoint, TargetFrame},
+    gestures::{
+        pointer::{InitialPointer, PointerId},
+        Handler,
+    },
+    instrument,
+    recognizer::{GestureRecognizer, RecognizerPhase, TrackedPointer},
+};
+
+/// A request for a context menu: a secondary click of a mouse, a long press of
+/// a touch pointer or a tap with the barrel button of a pen held.
+///
+/// The native `contextmenu` is suppressed on the target by default. Safari on
+/// iOS shows its callout regardless, unless the target is styled with
+/// `-webkit-touch-callout: none`.
+///
+/// Once a context menu was requested, the gesture keeps its pointer until it is
+/// released, so an exclusive [`Drag`] does not start from the same press.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_gestures::{
+///     state::gestures::context_menu::ContextMenu,
+///     use_gestures::{use_gestures, Gestures},
+/// };
+///
+/// #[component]
+/// pub fn ContextMenuExample() -> Element {
+///     let mut menu = use_signal(|| None);
+///
+///     let gestures = use_gestures(
+///         Gestures::default()
+///             .context_menu(ContextMenu::default().on_open(move |data| {
+///                 menu.set(Some(data.client_coordinates()));
+///             })),
+///     );
+///
+///     rsx! {
+///         div {
+///             style: "touch-action: none; -webkit-touch-callout: none;",
+///             ..gestures.event_handlers(),
+///             if let Some(position) = menu() {
+///                 "Menu at {position.x}, {position.y}"
+///             }
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Drag`]: crate::state::gestures::drag::Drag
+#[derive(Clone)]
+pub struct ContextMenu {
+    pub on_open: Option<Handler<ContextMenuData>>,
+    /// Milliseconds a touch pointer is held until a context menu is requested.
+    pub long_press_delay: f64,
+    /// The distance in pixels a touch pointer may move during the long press.
+    pub tolerance: f64,
+    pub suppress_native_menu: bool,
+}
+
+impl ContextMenu {
+    pub fn on_open(mut self, handler: impl FnMut(ContextMenuData) + 'static) -> Self {
+        self.on_open = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    pub fn long_press_delay(mut self, milliseconds: f64) -> Self {
+        self.long_press_delay = milliseconds;
+        self
+    }
+
+    pub fn tolerance(mut self, pixels: f64) -> Self {
+        self.tolerance = pixels;
+        self
+    }
+
+    pub fn suppress_native_menu(mut self, suppress_native_menu: bool) -> Self {
+        self.suppress_native_menu = suppress_native_menu;
+        self
+    }
+}
+
+impl Default for ContextMenu {
+    fn default() -> Self {
+        Self {
+            on_open: None,
+            long_press_delay: 500.0,
+            tolerance: 10.0,
+            suppress_native_menu: true,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextMenuTrigger {
+    SecondaryButton,
+    LongPress,
+    BarrelButton,
+}
+
+pub struct ContextMenuData {
+    pub trigger: ContextMenuTrigger,
+    pub pointer: InitialPointer,
+}
+
+impl ContextMenuData {
+    pub fn client_coordinates(&self) -> ClientPoint {
+        self.pointer.data.client_coordinates()
+    }
+
+    /// The position relative to the target element.
+    pub fn local_coordinates(&self) -> LocalPoint {
+        self.pointer.local_coordinates()
+    }
+}
+
+#[derive(Clone)]
+struct ContextMenuPointer {
+    id: PointerId,
+    initial_state: Rc<PointerData>,
+    current_state: Rc<PointerData>,
+    frame: Rc<TargetFrame>,
+}
+
+impl ContextMenuPointer {
+    fn new(pointer_data: Rc<PointerData>, frame: Rc<TargetFrame>) -> Self {
+        Self {
+            id: PointerId::from(pointer_data.pointer_id()),
+            initial_state: Rc::clone(&pointer_data),
+            current_state: pointer_data,
+            frame,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ContextMenuGestureState {
+    context_menu: ContextMenu,
+    arbiter: Option<Arbiter>,
+    frames: FrameSource,
+    /// The touch pointer of a long press that has not requested a context
+    /// menu yet, with the token of its timeout.
+    press: Option<(ContextMenuPointer, u64)>,
+    /// The pointer that requested a context menu, until it is released.
+    opened: Option<ContextMenuPointer>,
+    timeouts: Vec<(f64, u64)>,
+    next_token: u64,
+}
+
+impl ContextMenuGestureState {
+    pub fn new(context_menu: ContextMenu) -> Self {
+        Self {
+            context_menu,
+            arbiter: None,
+            frames: FrameSource::default(),
+            press: None,
+            opened: None,
+            timeouts: Vec::new(),
+            next_token: 0,
+        }
+    }
+
+    fn open(&mut self, pointer: ContextMenuPointer, trigger: ContextMenuTrigger) {
+        if let Some(arbiter) = &self.arbiter {
+            if !arbiter.try_start(&[pointer.id]) {
+                return;
+            }
+        }
+
+        instrument::event!(debug, ?trigger, "idle -> started");
+        instrument::event!(trace, callback = "open");
+        let pointer_data = InitialPointer {
+            data: Rc::clone(&pointer.current_state),
+            frame: Rc::clone(&pointer.frame),
+        };
+        self.opened = Some(pointer);
+        if let Some(handler) = &self.context_menu.on_open {
+            handler.borrow_mut()(ContextMenuData {
+                trigger,
+                pointer: pointer_data,
+            });
+        }
+    }
+
+    /// The trigger of a secondary button that was just pressed, if any.
+    fn secondary_button(pointer_data: &PointerData) -> Option<ContextMenuTrigger> {
+        let pressed = pointer_data.trigger_button() == Some(MouseButton::Secondary)
+            && pointer_data.held_buttons().contains(MouseButton::Secondary);
+        match pointer_data.pointer_type().as_str() {
+            "pen" if pressed || pointer_data.held_buttons().contains(MouseButton::Secondary) => {
+                Some(ContextMenuTrigger::BarrelButton)
+            }
+            "touch" => None,
+            _ if pressed => Some(ContextMenuTrigger::SecondaryButton),
+            _ => None,
+        }
+    }
+
+    fn press(&mut self, pointer_data: Rc<PointerData>) {
+        if self.press.is_some() || self.opened.is_some() {
+            instrument::event!(debug, "no long press with more than one touch");
+            self.press = None;
+            return;
+        }
+        self.next_token += 1;
+        self.timeouts
+            .push((self.context_menu.long_press_delay, self.next_token));
+        let frame = self.frames.measure(&pointer_data);
+        self.press = Some((
+            ContextMenuPointer::new(pointer_data, frame),
+            self.next_token,
+        ));
+    }
+
+    fn move_press(&mut self, pointer_data: Rc<PointerData>) {
+        let Some((press, _)) = &mut self.press else {
+            return;
+        };
+        if !press.id.is_equal_i32(pointer_data.pointer_id()) {
+            return;
+        }
+        let distance =
+            (pointer_data.client_coordinates() - press.initial_state.client_coordinates()).length();
+        press.current_state = pointer_data;
+        if distance > self.context_menu.tolerance {
+            instrument::event!(debug, "long press moved too far");
+            self.press = None;
+        }
+    }
+
+    fn release(&mut self, event: &Event<PointerData>) {
+        let pointer_id = event.pointer_id();
+        if self
+            .press
+            .as_ref()
+            .is_some_and(|(press, _)| press.id.is_equal_i32(pointer_id))
+        {
+            self.press = None;
+        }
+        if self
+            .opened
+            .as_ref()
+            .is_some_and(|opened| opened.id.is_equal_i32(pointer_id))
+        {
+            instrument::event!(debug, "started -> idle");
+            self.opened = None;
+        }
+    }
+
+    fn update_opened(&mut self, pointer_data: &Rc<PointerData>) {
+        if let Some(opened) = &mut self.opened {
+            if opened.id.is_equal_i32(pointer_data.pointer_id()) {
+                opened.current_state = Rc::clone(pointer_data);
+            }
+        }
+    }
+}
+
+impl PointerEventReceiver<&Event<PointerData>> for ContextMenuGestureState {
+    fn pointer_over(&mut self, _: &Event<PointerData>) {}
+
+    fn pointer_enter(&mut self, _: &Event<PointerData>) {}
+
+    fn pointer_down(&mut self, event: &Event<PointerData>) {
+        let pointer_data = event.data();
+        if pointer_data.pointer_type() == "touch" {
+            self.press(pointer_data);
+        } else if let Some(trigger) = Self::secondary_button(&pointer_data) {
+            let frame = self.frames.measure(&pointer_data);
+            self.open(ContextMenuPointer::new(pointer_data, frame), trigger);
+        }
+    }
+
+    fn pointer_move(&mut self, event: &Event<PointerData>) {
+        let pointer_data = event.data();
+        self.update_opened(&pointer_data);
+        if pointer_data.pointer_type() == "touch" {
+            self.move_press(pointer_data);
+        } else if self.opened.is_none() && pointer_data.trigger_button().is_some() {
+            // A secondary button pressed while another one is held.
+            if let Some(trigger) = Self::secondary_button(&pointer_data) {
+                let frame = self.frames.measure(&pointer_data);
+                self.open(ContextMenuPointer::new(pointer_data, frame), trigger);
+            }
+        }
+    }
+
+    fn pointer_up(&mut self, event: &Event<PointerData>) {
+        self.release(event);
+    }
+
+    fn pointer_cancel(&mut self, event: &Event<PointerData>) {
+        self.release(event);
+    }
+
+    fn pointer_out(&mut self, _: &Event<PointerData>) {}
+
+    fn pointer_leave(&mut self, event: &Event<PointerData>) {
+        self.release(event);
+    }
+}
+
+impl GestureRecognizer for ContextMenuGestureState {
+    fn attach(&mut self, arbiter: Arbiter) {
+        self.arbiter = Some(arbiter);
+    }
+
+    fn attach_frames(&mut self, frames: FrameSource) {
+        self.frames = frames;
+    }
+
+    fn take_timeouts(&mut self) -> Vec<(f64, u64)> {
+        std::mem::take(&mut self.timeouts)
+    }
+
+    fn timeout(&mut self, token: u64) {
+        match self.press.take() {
+            Some((press, press_token)) if press_token == token => {
+                self.open(press, ContextMenuTrigger::LongPress);
+            }
+            press => self.press = press,
+        }
+    }
+
+    fn phase(&self) -> RecognizerPhase {
+        match (&self.press, &self.opened) {
+            (_, Some(_)) => RecognizerPhase::Started,
+            (Some(_), None) => RecognizerPhase::Possible,
+            (None, None) => RecognizerPhase::Idle,
+        }
+    }
+
+    fn tracked_pointers(&self) -> Vec<TrackedPointer> {
+        self.press
+            .iter()
+            .map(|(press, _)| press)
+            .chain(&self.opened)
+            .map(|pointer| TrackedPointer {
+                id: pointer.id,
+                initial_data: Rc::clone(&pointer.initial_state),
+                current_data: Rc::clone(&pointer.current_state),
+                frame: Rc::clone(&pointer.frame),
+            })
+            .collect()
+    }
+
+    fn cancel(&mut self) {
+        self.press = None;
+        self.opened = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use dioxus::html::input_data::MouseButton;
+
+    use crate::{
+        state::{
+            gestures::{
+                context_menu::{ContextMenu, ContextMenuTrigger},
+                drag::Drag,
+            },
+            recognizer::RecognizerPhase,
+        },
+        testing::{CallbackLog, GestureHarness, PointerSequence, SyntheticPointer},
+        use_gestures::Gestures,
+    };
+
+    type Triggers = Rc<RefCell<Vec<(ContextMenuTrigger, f64, f64)>>>;
+
+    fn harness(log: &CallbackLog, triggers: &Triggers) -> GestureHarness {
+        let triggers = Rc::clone(triggers);
+        GestureHarness::new(
+            Gestures::default()
+                .drag(log.drag(Drag::default()))
+                .context_menu(ContextMenu::default().on_open(move |data| {
+                    let local = data.local_coordinates();
+                    triggers.borrow_mut().push((data.trigger, local.x, local.y));
+                })),
+        )
+    }
+
+    fn secondary(pointer: SyntheticPointer) -> SyntheticPointer {
+        pointer.buttons(Some(MouseButton::Secondary), MouseButton::Secondary.into())
+    }
+
+    #[test]
+    fn opens_on_a_secondary_click() {
+        let log = CallbackLog::default();
+        let triggers = Rc::default();
+        let mut harness = harness(&log, &triggers);
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::mouse().at(4.0, 8.0))
+                .up(1),
+        );
+        assert!(triggers.borrow().is_empty());
+
+        harness.play(
+            PointerSequence::default()
+                .down(secondary(SyntheticPointer::mouse().at(4.0, 8.0)))
+                .move_to(secondary(SyntheticPointer::mouse().at(40.0, 8.0)))
+                .up(1),
+        );
+        assert_eq!(
+            *triggers.borrow(),
+            [(ContextMenuTrigger::SecondaryButton, 4.0, 8.0)]
+        );
+        // The pointer belongs to the context menu until it is released.
+        log.assert_eq(&[]);
+        assert_eq!(harness.phase("context_menu"), Some(RecognizerPhase::Idle));
+    }
+
+    #[test]
+    fn opens_on_a_barrel_button_tap() {
+        let log = CallbackLog::default();
+        let triggers = Rc::default();
+        let mut harness = harness(&log, &triggers);
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::pen(2).at(1.0, 2.0).pressure(0.5).buttons(
+                    Some(MouseButton::Primary),
+                    MouseButton::Primary | MouseButton::Secondary,
+                ))
+                .up(2),
+        );
+        assert_eq!(
+            *triggers.borrow(),
+            [(ContextMenuTrigger::BarrelButton, 1.0, 2.0)]
+        );
+    }
+
+    #[test]
+    fn opens_on_a_long_press() {
+        let log = CallbackLog::default();
+        let triggers = Rc::default();
+        let mut harness = harness(&log, &triggers);
+
+        harness
+            .play(PointerSequence::default().down(SyntheticPointer::touch(1).at(10.0, 10.0)))
+            .play(PointerSequence::default().move_to(SyntheticPointer::touch(1).at(13.0, 10.0)))
+            .advance(499.0);
+        assert!(triggers.borrow().is_empty());
+        assert_eq!(
+            harness.phase("context_menu"),
+            Some(RecognizerPhase::Possible)
+        );
+        harness.advance(1.0);
+        assert_eq!(
+            *triggers.borrow(),
+            [(ContextMenuTrigger::LongPress, 13.0, 10.0)]
+        );
+        harness.play(
+            PointerSequence::default()
+                .move_to(SyntheticPointer::touch(1).at(13.0, 10.0))
+                .up(1),
+        );
+
+        // Moving, lifting early or adding a second touch is no long press.
+        harness
+            .play(
+                PointerSequence::default()
+                    .down(SyntheticPointer::touch(2))
+                    .move_to(SyntheticPointer::touch(2).at(30.0, 0.0))
+                    .up(2),
+            )
+            .play(
+                PointerSequence::default()
+                    .down(SyntheticPointer::touch(3))
+                    .up(3),
+            )
+            .play(
+                PointerSequence::default()
+                    .down(SyntheticPointer::touch(4))
+                    .down(SyntheticPointer::touch(5)),
+            )
+            .advance(500.0);
+        assert_eq!(triggers.borrow().len(), 1);
+        log.assert_eq(&["drag.start", "drag.end"]);
+    }
+}
@@ -4,23 +4,82 @@ use dioxus::core::Event;
 use dioxus::html::PointerData;
 
 use crate::state::{
+    arbitration::Arbiter,
+    delivery::UpdateDelivery,
     events::PointerEventReceiver,
-    gestures::{
-        drag::{Drag, DragCancelData, DragEndData, DragStartData, DragUpdateData},
-        pinch::{
-            Pinch, PinchCancelData, PinchEndData, PinchStartData, PinchUpdateData,
-            PinchUpdatedPointer,
-        },
-        pointer::{IncrementalOffsetPointer, InitialPointer, OffsetPointer, PointerId},
-    },
+    frame::{FrameMapping, FrameSource, TargetFrame},
+    gestures::pointer::{IncrementalOffsetPointer, InitialPointer, OffsetPointer, PointerId},
+    instrument,
+    recognizer::{GestureRecognizer, RecognizerPhase, TrackedPointer},
 };
 
+/// A gesture made of a fixed number of down pointers, like [`Drag`] and
+/// [`Pinch`]. The pointers passed to it always have [`Self::POINTERS`]
+/// elements, in the order the pointers went down.
+///
+/// [`Drag`]: crate::state::gestures::drag::Drag
+/// [`Pinch`]: crate::state::gestures::pinch::Pinch
+pub trait DownPointerGesture {
+    const POINTERS: usize;
+
+    fn has_started(&self, pointers: &[IncrementalOffsetPointer]) -> bool;
+
+    fn start(&self, pointers: Vec<InitialPointer>);
+
+    /// Called with the index of the pointer that moved, or `None` if several
+    /// pointers moved since the preceding update.
+    fn update(&self, pointers: Vec<IncrementalOffsetPointer>, updated_pointer: Option<usize>);
+
+    /// Whether an update is held back until every pointer has moved, or until
+    /// the next animation frame if some of them stay still.
+    fn joint_updates(&self) -> bool {
+        false
+    }
+
+    /// The mapping of the local coordinates of the gesture, if it differs from
+    /// the [`UseGesturesOptions::frame_mapping`] of the target.
+    ///
+    /// [`UseGesturesOptions::frame_mapping`]: crate::state::options::UseGesturesOptions::frame_mapping
+    fn frame_mapping(&self) -> Option<FrameMapping> {
+        None
+    }
+
+    fn end(&self, pointers: Vec<OffsetPointer>);
+
+    fn cancel(&self, pointers: Vec<OffsetPointer>);
+}
+
 #[derive(Clone)]
-pub struct DownPointerGestureState {
-    drag: Drag,
-    pinch: Pinch,
+pub struct DownPointerGestureState<G> {
+    gesture: G,
     pointers: Vec<DownPointerState>,
     gesture_state: GestureState,
+    arbiter: Option<Arbiter>,
+    frames: FrameSource,
+    frame: Rc<TargetFrame>,
+    update_delivery: UpdateDelivery,
+    pending_update: Option<PendingUpdate>,
+}
+
+/// An update held back until the next animation frame, or until every
+/// pointer has moved.
+#[derive(Clone)]
+struct PendingUpdate {
+    /// The data of every pointer at the preceding update.
+    preceding: Vec<Rc<PointerData>>,
+    /// The moves of every pointer since then, but for the latest.
+    held_back: Vec<Vec<Rc<PointerData>>>,
+    moved: Vec<bool>,
+}
+
+impl PendingUpdate {
+    fn updated_pointer(&self) -> Option<usize> {
+        let mut moved = self.moved.iter().enumerate().filter(|(_, moved)| **moved);
+        match (moved.next(), moved.next()) {
+            (Some((index, _)), None) => Some(index),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Clone, Default)]
@@ -28,6 +87,9 @@ enum GestureState {
     #[default]
     Pending,
     Started,
+    /// Cancelled by arbitration, the gesture stays inactive until all pointers
+    /// are released.
+    Cancelled,
 }
 
 #[derive(Clone)]
@@ -37,489 +99,265 @@ struct DownPointerState {
     current: Rc<PointerData>,
 }
 
-impl DownPointerGestureState {
-    pub fn new(drag: Drag, pinch: Pinch) -> Self {
+enum Completion {
+    End,
+    Cancel,
+}
+
+impl<G: DownPointerGesture> DownPointerGestureState<G> {
+    pub fn new(gesture: G) -> Self {
         Self {
-            drag,
-            pinch,
+            gesture,
             pointers: Vec::new(),
             gesture_state: GestureState::default(),
+            arbiter: None,
+            frames: FrameSource::default(),
+            frame: Rc::default(),
+            update_delivery: UpdateDelivery::default(),
+            pending_update: None,
         }
     }
 
-    fn add_down_pointer_event(&mut self, pointer_data: Rc<PointerData>) {
-        self.pointers.push(DownPointerState {
-            id: PointerId::from(pointer_data.pointer_id()),
-            initial: Rc::clone(&pointer_data),
-            current: Rc::clone(&pointer_data),
-        });
+    fn is_complete(&self) -> bool {
+        self.pointers.len() == G::POINTERS
+    }
 
-        match self.pointers.len() {
-            1 => {
-                self.gesture_state = match (self.drag.has_started)([&pointer_data, &pointer_data]) {
-                    false => GestureState::Pending,
-                    true => GestureState::Started,
-                };
-
-                match self.gesture_state {
-                    GestureState::Started => {
-                        if let Some(handler) = &self.drag.on_start {
-                            handler.borrow_mut()(DragStartData {
-                                pointer: InitialPointer { data: pointer_data },
-                            })
-                        }
-                    }
-                    _ => (),
-                }
-            }
-            2 => {
-                if let Some(handler) = &self.drag.on_end {
-                    let existing_pointer = &self.pointers[0];
-                    handler.borrow_mut()(DragEndData {
-                        pointer: OffsetPointer {
-                            initial_data: Rc::clone(&existing_pointer.initial),
-                            final_data: Rc::clone(&pointer_data),
-                        },
-                    });
-                }
+    fn incremental_pointers(&self) -> Vec<IncrementalOffsetPointer> {
+        self.pointers
+            .iter()
+            .map(|pointer| IncrementalOffsetPointer {
+                initial_data: Rc::clone(&pointer.initial),
+                preceding_data: Rc::clone(&pointer.current),
+                current_data: Rc::clone(&pointer.current),
+                held_back_data: Vec::new(),
+                frame: Rc::clone(&self.frame),
+            })
+            .collect()
+    }
 
-                self.pointers[0].initial = Rc::clone(&self.pointers[0].current);
-
-                self.gesture_state = match (self.pinch.has_started)(
-                    [&self.pointers[0].initial, &self.pointers[0].initial],
-                    [&pointer_data, &pointer_data],
-                ) {
-                    false => GestureState::Pending,
-                    true => GestureState::Started,
-                };
-
-                match self.gesture_state {
-                    GestureState::Started => {
-                        if let Some(handler) = &self.pinch.on_start {
-                            handler.borrow_mut()(PinchStartData {
-                                pointers: [
-                                    InitialPointer {
-                                        data: Rc::clone(&self.pointers[0].initial),
-                                    },
-                                    InitialPointer { data: pointer_data },
-                                ],
-                            });
-                        }
-                    }
-                    _ => (),
-                }
-            }
-            3 => {
-                if let Some(handler) = &self.pinch.on_end {
-                    let [first, second]: &[DownPointerState; 2] =
-                        self.pointers[0..=1].try_into().unwrap();
-                    handler.borrow_mut()(PinchEndData {
-                        pointers: [
-                            {
-                                OffsetPointer {
-                                    initial_data: Rc::clone(&first.initial),
-                                    final_data: Rc::clone(&first.current),
-                                }
-                            },
-                            {
-                                OffsetPointer {
-                                    initial_data: Rc::clone(&second.initial),
-                                    final_data: Rc::clone(&second.current),
-                                }
-                            },
-                        ],
-                    });
-                }
+    fn offset_pointers(&self) -> Vec<OffsetPointer> {
+        self.pointers
+            .iter()
+            .map(|pointer| OffsetPointer {
+                initial_data: Rc::clone(&pointer.initial),
+                final_data: Rc::clone(&pointer.current),
+                frame: Rc::clone(&self.frame),
+            })
+            .collect()
+    }
+
+    fn try_start(&mut self) {
+        let has_started = self.gesture.has_started(&self.incremental_pointers());
+        instrument::event!(trace, has_started, "threshold check");
+        if !has_started {
+            return;
+        }
+        if let Some(arbiter) = &self.arbiter {
+            let pointers = self
+                .pointers
+                .iter()
+                .map(|pointer| pointer.id)
+                .collect::<Vec<_>>();
+            if !arbiter.try_start(&pointers) {
+                return;
             }
-            _ => {}
         }
+
+        instrument::event!(debug, "pending -> started");
+        self.gesture_state = GestureState::Started;
+        instrument::event!(trace, callback = "start");
+        self.gesture.start(
+            self.pointers
+                .iter()
+                .map(|pointer| InitialPointer {
+                    data: Rc::clone(&pointer.initial),
+                    frame: Rc::clone(&self.frame),
+                })
+                .collect(),
+        );
     }
 
-    fn update_known_down_pointer_event(&mut self, index: usize, pointer_data: Rc<PointerData>) {
-        let initial_data = Rc::clone(&self.pointers[index].initial);
-        let preceding_data = Rc::clone(&self.pointers[index].current);
-        self.pointers[index].current = Rc::clone(&pointer_data);
-
-        match self.pointers.len() {
-            1 => {
-                match self.gesture_state {
-                    GestureState::Pending => {
-                        match (self.drag.has_started)([&initial_data, &pointer_data]) {
-                            false => (),
-                            true => {
-                                self.gesture_state = GestureState::Started;
-                                if let Some(handler) = &self.drag.on_start {
-                                    handler.borrow_mut()(DragStartData {
-                                        pointer: InitialPointer {
-                                            data: Rc::clone(&initial_data),
-                                        },
-                                    });
-                                }
-                            }
-                        }
-                    }
-                    GestureState::Started => match &self.drag.on_update {
-                        Some(handler) => {
-                            (handler.borrow_mut())(DragUpdateData {
-                                pointer: IncrementalOffsetPointer {
-                                    initial_data,
-                                    preceding_data,
-                                    current_data: Rc::clone(&pointer_data),
-                                },
-                            });
-                        }
-                        None => (),
-                    },
-                };
-            }
-            2 => {
-                let [first, second]: &[DownPointerState; 2] =
-                    self.pointers[0..=1].try_into().unwrap();
-                match self.gesture_state {
-                    GestureState::Pending => match (self.pinch.has_started)(
-                        [&first.initial, &first.current],
-                        [&second.initial, &second.current],
-                    ) {
-                        false => (),
-                        true => {
-                            self.gesture_state = GestureState::Started;
-                            if let Some(handler) = &self.pinch.on_start {
-                                handler.borrow_mut()(PinchStartData {
-                                    pointers: [
-                                        InitialPointer {
-                                            data: Rc::clone(&first.initial),
-                                        },
-                                        InitialPointer {
-                                            data: Rc::clone(&second.initial),
-                                        },
-                                    ],
-                                });
-                            }
-                        }
-                    },
-                    GestureState::Started => {
-                        if let Some(handler) = &self.pinch.on_update {
-                            let (pointers, updated_pointer) = if index == 0 {
-                                (
-                                    [
-                                        IncrementalOffsetPointer {
-                                            initial_data: Rc::clone(&initial_data),
-                                            preceding_data,
-                                            current_data: pointer_data,
-                                        },
-                                        IncrementalOffsetPointer {
-                                            initial_data: Rc::clone(&second.initial),
-                                            preceding_data: Rc::clone(&second.current),
-                                            current_data: Rc::clone(&second.current),
-                                        },
-                                    ],
-                                    PinchUpdatedPointer::First,
-                                )
-                            } else {
-                                (
-                                    [
-                                        IncrementalOffsetPointer {
-                                            initial_data: Rc::clone(&first.initial),
-                                            preceding_data: Rc::clone(&first.current),
-                                            current_data: Rc::clone(&first.current),
-                                        },
-                                        IncrementalOffsetPointer {
-                                            initial_data: Rc::clone(&initial_data),
-                                            preceding_data,
-                                            current_data: pointer_data,
-                                        },
-                                    ],
-                                    PinchUpdatedPointer::Second,
-                                )
-                            };
-                            handler.borrow_mut()(PinchUpdateData {
-                                pointers,
-                                updated_pointer,
-                            });
-                        }
-                    }
-                };
-            }
-            _ => {}
+    /// Starts tracking the gesture from the current position of its pointers.
+    fn restart(&mut self) {
+        if let GestureState::Cancelled = self.gesture_state {
+            return;
         }
+
+        self.pointers
+            .iter_mut()
+            .for_each(|pointer| pointer.initial = Rc::clone(&pointer.current));
+        self.gesture_state = GestureState::Pending;
+        self.try_start();
     }
 
-    fn remove_known_down_pointer_event(&mut self, index: usize, pointer_data: Rc<PointerData>) {
-        let pointer = self.pointers.remove(index);
-        let initial_data = pointer.initial;
-
-        match self.pointers.len() {
-            0 => {
-                match self.gesture_state {
-                    GestureState::Pending => (),
-                    GestureState::Started => {
-                        if let Some(handler) = &self.drag.on_end {
-                            handler.borrow_mut()(DragEndData {
-                                pointer: OffsetPointer {
-                                    initial_data,
-                                    final_data: pointer_data,
-                                },
-                            });
-                        }
-                    }
-                };
-            }
-            1 => {
-                match self.gesture_state {
-                    GestureState::Pending => (),
-                    GestureState::Started => {
-                        if let Some(handler) = &self.pinch.on_end {
-                            let removed = OffsetPointer {
-                                initial_data,
-                                final_data: pointer_data,
-                            };
-                            let pointer = &self.pointers[0];
-                            let retained = OffsetPointer {
-                                initial_data: Rc::clone(&pointer.initial),
-                                final_data: Rc::clone(&pointer.current),
-                            };
-                            handler.borrow_mut()(PinchEndData {
-                                pointers: if index == 0 {
-                                    [removed, retained]
-                                } else {
-                                    [retained, removed]
-                                },
-                            });
-                        }
-                    }
-                };
-
-                self.pointers[0].initial = Rc::clone(&self.pointers[0].current);
-
-                self.gesture_state = match (self.drag.has_started)([
-                    &self.pointers[0].current,
-                    &self.pointers[0].current,
-                ]) {
-                    false => GestureState::Pending,
-                    true => GestureState::Started,
-                };
-
-                match self.gesture_state {
-                    GestureState::Started => {
-                        if let Some(handler) = &self.drag.on_start {
-                            handler.borrow_mut()(DragStartData {
-                                pointer: InitialPointer {
-                                    data: Rc::clone(&self.pointers[0].initial),
-                                },
-                            });
-                        }
-                    }
-                    _ => (),
+    fn deliver_pending_update(&mut self) {
+        let Some(pending) = self.pending_update.take() else {
+            return;
+        };
+        if !matches!(self.gesture_state, GestureState::Started) {
+            return;
+        }
+        let updated_pointer = pending.updated_pointer();
+        let mut pointers = self.incremental_pointers();
+        for ((pointer, preceding), held_back) in pointers
+            .iter_mut()
+            .zip(pending.preceding)
+            .zip(pending.held_back)
+        {
+            pointer.preceding_data = preceding;
+            pointer.held_back_data = held_back;
+        }
+        instrument::event!(trace, callback = "update", ?updated_pointer);
+        self.gesture.update(pointers, updated_pointer);
+    }
+
+    fn complete(&mut self, completion: Completion) {
+        self.deliver_pending_update();
+        if let GestureState::Started = self.gesture_state {
+            self.gesture_state = GestureState::Pending;
+            match completion {
+                Completion::End => {
+                    instrument::event!(debug, "started -> pending (end)");
+                    instrument::event!(trace, callback = "end");
+                    self.gesture.end(self.offset_pointers());
                 }
-            }
-            2 => {
-                self.pointers
-                    .iter_mut()
-                    .for_each(|pointer| pointer.initial = Rc::clone(&pointer.current));
-
-                let [first, second]: &[DownPointerState; 2] =
-                    self.pointers[0..=1].try_into().unwrap();
-
-                self.gesture_state = match (self.pinch.has_started)(
-                    [&first.initial, &first.current],
-                    [&second.initial, &second.current],
-                ) {
-                    false => GestureState::Pending,
-                    true => GestureState::Started,
-                };
-
-                match self.gesture_state {
-                    GestureState::Started => {
-                        if let Some(handler) = &self.pinch.on_start {
-                            handler.borrow_mut()(PinchStartData {
-                                pointers: [
-                                    InitialPointer {
-                                        data: Rc::clone(&first.initial),
-                                    },
-                                    InitialPointer {
-                                        data: Rc::clone(&second.initial),
-                                    },
-                                ],
-                            });
-                        }
-                    }
-                    _ => (),
+                Completion::Cancel => {
+                    instrument::event!(debug, "started -> pending (cancel)");
+                    instrument::event!(trace, callback = "cancel");
+                    self.gesture.cancel(self.offset_pointers());
                 }
             }
-            _ => {}
         }
     }
 
-    fn cancel_known_down_pointer_event(&mut self, index: usize, pointer_data: Rc<PointerData>) {
-        let pointer = self.pointers.remove(index);
-        let initial_data = pointer.initial;
-
-        match self.pointers.len() {
-            0 => {
-                match self.gesture_state {
-                    GestureState::Pending => (),
-                    GestureState::Started => {
-                        if let Some(handler) = &self.drag.on_cancel {
-                            handler.borrow_mut()(DragCancelData {
-                                pointer: OffsetPointer {
-                                    initial_data,
-                                    final_data: pointer_data,
-                                },
-                            });
-                        }
-                    }
-                };
+    fn add_down_pointer_event(&mut self, pointer_data: Rc<PointerData>) {
+        if self.is_complete() {
+            self.complete(Completion::End);
+        }
+
+        if self.pointers.is_empty() {
+            self.frame = match self.gesture.frame_mapping() {
+                Some(mapping) => self.frames.measure_with(&pointer_data, mapping),
+                None => self.frames.measure(&pointer_data),
+            };
+        }
+        self.pointers.push(DownPointerState {
+            id: PointerId::from(pointer_data.pointer_id()),
+            initial: Rc::clone(&pointer_data),
+            current: pointer_data,
+        });
+
+        if self.is_complete() {
+            self.restart();
+        }
+    }
+
+    fn update_known_down_pointer_event(&mut self, index: usize, pointer_data: Rc<PointerData>) {
+        let preceding_data = std::mem::replace(&mut self.pointers[index].current, pointer_data);
+
+        if !self.is_complete() {
+            return;
+        }
+
+        match self.gesture_state {
+            GestureState::Pending => self.try_start(),
+            GestureState::Started
+                if self.update_delivery == UpdateDelivery::Immediate
+                    && !self.gesture.joint_updates() =>
+            {
+                let mut pointers = self.incremental_pointers();
+                pointers[index].preceding_data = preceding_data;
+                instrument::event!(trace, callback = "update", updated_pointer = index);
+                self.gesture.update(pointers, Some(index));
             }
-            1 => {
-                match self.gesture_state {
-                    GestureState::Pending => (),
-                    GestureState::Started => {
-                        if let Some(handler) = &self.pinch.on_cancel {
-                            let removed = OffsetPointer {
-                                initial_data,
-                                final_data: pointer_data,
-                            };
-                            let pointer = &self.pointers[0];
-                            let retained = OffsetPointer {
-                                initial_data: Rc::clone(&pointer.initial),
-                                final_data: Rc::clone(&pointer.current),
-                            };
-                            handler.borrow_mut()(PinchCancelData {
-                                pointers: if index == 0 {
-                                    [removed, retained]
-                                } else {
-                                    [retained, removed]
-                                },
-                            });
-                        }
-                    }
-                };
-
-                self.pointers[0].initial = Rc::clone(&self.pointers[0].current);
-
-                self.gesture_state = match (self.drag.has_started)([
-                    &self.pointers[0].current,
-                    &self.pointers[0].current,
-                ]) {
-                    false => GestureState::Pending,
-                    true => GestureState::Started,
-                };
-
-                match self.gesture_state {
-                    GestureState::Started => {
-                        if let Some(handler) = &self.drag.on_start {
-                            handler.borrow_mut()(DragStartData {
-                                pointer: InitialPointer {
-                                    data: Rc::clone(&self.pointers[0].initial),
-                                },
-                            });
-                        }
-                    }
-                    _ => (),
+            GestureState::Started => {
+                let pending = self.pending_update.get_or_insert_with(|| PendingUpdate {
+                    preceding: self
+                        .pointers
+                        .iter()
+                        .enumerate()
+                        .map(|(i, pointer)| match i == index {
+                            true => Rc::clone(&preceding_data),
+                            false => Rc::clone(&pointer.current),
+                        })
+                        .collect(),
+                    held_back: vec![Vec::new(); G::POINTERS],
+                    moved: vec![false; G::POINTERS],
+                });
+                if pending.moved[index] {
+                    pending.held_back[index].push(preceding_data);
                 }
-            }
-            2 => {
-                self.pointers
-                    .iter_mut()
-                    .for_each(|pointer| pointer.initial = Rc::clone(&pointer.current));
-
-                let [first, second]: &[DownPointerState; 2] =
-                    self.pointers[0..=1].try_into().unwrap();
-
-                self.gesture_state = match (self.pinch.has_started)(
-                    [&first.initial, &first.current],
-                    [&second.initial, &second.current],
-                ) {
-                    false => GestureState::Pending,
-                    true => GestureState::Started,
-                };
-
-                match self.gesture_state {
-                    GestureState::Started => {
-                        if let Some(handler) = &self.pinch.on_start {
-                            handler.borrow_mut()(PinchStartData {
-                                pointers: [
-                                    InitialPointer {
-                                        data: Rc::clone(&first.initial),
-                                    },
-                                    InitialPointer {
-                                        data: Rc::clone(&second.initial),
-                                    },
-                                ],
-                            });
-                        }
-                    }
-                    _ => (),
+                pending.moved[index] = true;
+                if self.gesture.joint_updates() && pending.moved.iter().all(|moved| *moved) {
+                    self.deliver_pending_update();
                 }
             }
-            _ => {}
+            GestureState::Cancelled => (),
         }
     }
 
-    fn add_or_update(&mut self, event: &Event<PointerData>) {
-        let pointer_data = event.data();
-        let pointer_id = pointer_data.pointer_id();
-        let associated_down_pointer_event = self
-            .pointers
+    fn remove_known_down_pointer_event(
+        &mut self,
+        index: usize,
+        pointer_data: Rc<PointerData>,
+        completion: Completion,
+    ) {
+        self.deliver_pending_update();
+        self.pointers[index].current = pointer_data;
+
+        if self.is_complete() {
+            self.complete(completion);
+        }
+
+        self.pointers.remove(index);
+
+        if self.pointers.is_empty() {
+            self.gesture_state = GestureState::Pending;
+        } else if self.is_complete() {
+            self.restart();
+        }
+    }
+
+    fn position(&self, event: &Event<PointerData>) -> Option<usize> {
+        let pointer_id = event.data().pointer_id();
+        self.pointers
             .iter()
-            .position(|p| p.id.is_equal_i32(pointer_id));
-        match associated_down_pointer_event {
+            .position(|p| p.id.is_equal_i32(pointer_id))
+    }
+
+    fn add_or_update(&mut self, event: &Event<PointerData>) {
+        match self.position(event) {
             Some(position) => {
-                self.update_known_down_pointer_event(position, pointer_data);
+                self.update_known_down_pointer_event(position, event.data());
             }
             None => {
-                self.add_down_pointer_event(pointer_data);
+                self.add_down_pointer_event(event.data());
             }
         };
     }
 
     fn update(&mut self, event: &Event<PointerData>) {
-        let pointer_data = event.data();
-        let pointer_id = pointer_data.pointer_id();
-        let associated_down_pointer_event = self
-            .pointers
-            .iter()
-            .position(|p| p.id.is_equal_i32(pointer_id));
-        match associated_down_pointer_event {
-            Some(position) => {
-                self.update_known_down_pointer_event(position, pointer_data);
-            }
-            None => {}
-        };
+        if let Some(position) = self.position(event) {
+            self.update_known_down_pointer_event(position, event.data());
+        }
     }
 
     fn remove(&mut self, event: &Event<PointerData>) {
-        let pointer_data = event.data();
-        let pointer_id = pointer_data.pointer_id();
-        let associated_down_pointer_event = self
-            .pointers
-            .iter()
-            .position(|p| p.id.is_equal_i32(pointer_id));
-        match associated_down_pointer_event {
-            Some(position) => {
-                self.remove_known_down_pointer_event(position, pointer_data);
-            }
-            None => {}
-        };
+        if let Some(position) = self.position(event) {
+            self.remove_known_down_pointer_event(position, event.data(), Completion::End);
+        }
     }
 
     fn cancel(&mut self, event: &Event<PointerData>) {
-        let pointer_data = event.data();
-        let pointer_id = pointer_data.pointer_id();
-        let associacted_hover_event = self
-            .pointers
-            .iter()
-            .position(|p| p.id.is_equal_i32(pointer_id));
-        match associacted_hover_event {
-            Some(position) => {
-                self.cancel_known_down_pointer_event(position, pointer_data);
-            }
-            None => {}
-        };
+        if let Some(position) = self.position(event) {
+            self.remove_known_down_pointer_event(position, event.data(), Completion::Cancel);
+        }
     }
 }
 
-impl PointerEventReceiver<&Event<PointerData>> for DownPointerGestureState {
+impl<G: DownPointerGesture> PointerEventReceiver<&Event<PointerData>>
+    for DownPointerGestureState<G>
+{
     fn pointer_over(&mut self, _: &Event<PointerData>) {}
 
     fn pointer_enter(&mut self, _: &Event<PointerData>) {}
@@ -547,4 +385,183 @@ impl PointerEventReceiver<&Event<PointerData>> for DownPointerGestureState {
     fn pointer_leave(&mut self, event: &Event<PointerData>) {
         self.remove(event);
     }
+
+    fn lost_pointer_capture(&mut self, event: &Event<PointerData>) {
+        self.cancel(event);
+    }
+}
+
+impl<G: DownPointerGesture> GestureRecognizer for DownPointerGestureState<G> {
+    fn attach(&mut self, arbiter: Arbiter) {
+        self.arbiter = Some(arbiter);
+    }
+
+    fn attach_frames(&mut self, frames: FrameSource) {
+        self.frames = frames;
+    }
+
+    fn set_update_delivery(&mut self, delivery: UpdateDelivery) {
+        self.update_delivery = delivery;
+    }
+
+    fn has_pending_updates(&self) -> bool {
+        self.pending_update.is_some()
+    }
+
+    fn flush_updates(&mut self) {
+        self.deliver_pending_update();
+    }
+
+    fn phase(&self) -> RecognizerPhase {
+        match self.gesture_state {
+            _ if self.pointers.is_empty() => RecognizerPhase::Idle,
+            GestureState::Pending => RecognizerPhase::Possible,
+            GestureState::Started => RecognizerPhase::Started,
+            GestureState::Cancelled => RecognizerPhase::Idle,
+        }
+    }
+
+    fn tracked_pointers(&self) -> Vec<TrackedPointer> {
+        self.pointers
+            .iter()
+            .map(|pointer| TrackedPointer {
+                id: pointer.id,
+                initial_data: Rc::clone(&pointer.initial),
+                current_data: Rc::clone(&pointer.current),
+                frame: Rc::clone(&self.frame),
+            })
+            .collect()
+    }
+
+    fn cancel(&mut self) {
+        self.complete(Completion::Cancel);
+        if !self.pointers.is_empty() {
+            instrument::event!(debug, "cancelled by arbitration");
+            self.gesture_state = GestureState::Cancelled;
+        }
+    }
+
+    fn resume(&mut self) {
+        if let (true, GestureState::Pending) = (self.is_complete(), &self.gesture_state) {
+            self.try_start();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use proptest::prelude::*;
+
+    use crate::{
+        state::{
+            gestures::{
+                drag::{Drag, DragCancelData, DragEndData, DragStartData, DragUpdateData},
+                pinch::{Pinch, PinchCancelData, PinchEndData, PinchStartData, PinchUpdateData},
+            },
+            recognizer::RecognizerPhase,
+        },
+        testing::{arbitrary_sequence, GestureHarness},
+        use_gestures::Gestures,
+    };
+
+    type Calls = Rc<RefCell<Vec<(&'static str, Vec<i32>)>>>;
+
+    fn record<T>(calls: &Calls, name: &'static str, ids: fn(&T) -> Vec<i32>) -> impl FnMut(T) {
+        let calls = Rc::clone(calls);
+        move |data| calls.borrow_mut().push((name, ids(&data)))
+    }
+
+    fn harness(calls: &Calls) -> GestureHarness {
+        GestureHarness::new(
+            Gestures::default()
+                .drag(
+                    Drag::default()
+                        .on_start(record(calls, "drag.start", |d: &DragStartData| {
+                            vec![d.pointer.data.pointer_id()]
+                        }))
+                        .on_update(record(calls, "drag.update", |d: &DragUpdateData| {
+                            vec![d.pointer.current_data.pointer_id()]
+                        }))
+                        .on_end(record(calls, "drag.end", |d: &DragEndData| {
+                            vec![d.pointer.final_data.pointer_id()]
+                        }))
+                        .on_cancel(record(calls, "drag.cancel", |d: &DragCancelData| {
+                            vec![d.pointer.final_data.pointer_id()]
+                        })),
+                )
+                .pinch(
+                    Pinch::default()
+                        .on_start(record(calls, "pinch.start", |d: &PinchStartData| {
+                            d.pointers.iter().map(|p| p.data.pointer_id()).collect()
+                        }))
+                        .on_update(record(calls, "pinch.update", |d: &PinchUpdateData| {
+                            d.pointers
+                                .iter()
+                                .map(|p| p.current_data.pointer_id())
+                                .collect()
+                        }))
+                        .on_end(record(calls, "pinch.end", |d: &PinchEndData| {
+                            d.pointers
+                                .iter()
+                                .map(|p| p.final_data.pointer_id())
+                                .collect()
+                        }))
+                        .on_cancel(record(calls, "pinch.cancel", |d: &PinchCancelData| {
+                            d.pointers
+                                .iter()
+                                .map(|p| p.final_data.pointer_id())
+                                .collect()
+                        })),
+                ),
+        )
+    }
+
+    /// Checks that the calls of `gesture` are made of
+    /// `start update* (end | cancel)` runs that all report the pointers the
+    /// gesture started with.
+    fn check_lifecycle(calls: &[(&'static str, Vec<i32>)], gesture: &str) -> Result<(), String> {
+        let mut started: Option<&Vec<i32>> = None;
+        for (name, ids) in calls {
+            let Some(event) = name.strip_prefix(gesture).and_then(|n| n.strip_prefix('.')) else {
+                continue;
+            };
+            match (event, started) {
+                ("start", None) => started = Some(ids),
+                ("update", Some(start)) if start == ids => {}
+                ("end" | "cancel", Some(start)) if start == ids => started = None,
+                _ => return Err(format!("{name} {ids:?} after start {started:?}")),
+            }
+        }
+        match started {
+            Some(ids) => Err(format!("{gesture} started with {ids:?} never finished")),
+            None => Ok(()),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn gestures_finish_with_the_pointers_they_started_with(sequence in arbitrary_sequence()) {
+            let calls = Calls::default();
+            let mut harness = harness(&calls);
+            harness.play(sequence);
+
+            let calls = calls.borrow();
+            prop_assert_eq!(check_lifecycle(&calls, "drag"), Ok(()));
+            prop_assert_eq!(check_lifecycle(&calls, "pinch"), Ok(()));
+        }
+
+        #[test]
+        fn no_pointers_remain_after_all_are_released(sequence in arbitrary_sequence()) {
+            let mut harness = harness(&Calls::default());
+            harness.play(sequence);
+
+            for name in ["drag", "pinch"] {
+                prop_assert!(harness.tracked_pointers(name).is_empty());
+                prop_assert_eq!(harness.phase(name), Some(RecognizerPhase::Idle));
+            }
+            prop_assert!(harness.captured_pointers().is_empty());
+        }
+    }
 }
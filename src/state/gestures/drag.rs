@@ -1,8 +1,15 @@
 use std::{cell::RefCell, rc::Rc};
-use dioxus::html::point_interaction::InteractionLocation;
-use dioxus::{html::PointerData};
 
-use crate::state::gestures::pointer::{IncrementalOffsetPointer, InitialPointer, OffsetPointer};
+use dioxus::html::{point_interaction::InteractionLocation, PointerData};
+
+use crate::state::{
+    frame::FrameMapping,
+    gestures::{
+        down_pointer::DownPointerGesture,
+        pointer::{IncrementalOffsetPointer, InitialPointer, OffsetPointer},
+        Handler, StartPredicate,
+    },
+};
 
 /// ```rust
 /// use dioxus::{
@@ -54,11 +61,12 @@ use crate::state::gestures::pointer::{IncrementalOffsetPointer, InitialPointer,
 /// ```
 #[derive(Clone)]
 pub struct Drag {
-    pub on_start: Option<Rc<RefCell<dyn FnMut(DragStartData)>>>,
-    pub on_update: Option<Rc<RefCell<dyn FnMut(DragUpdateData)>>>,
-    pub on_end: Option<Rc<RefCell<dyn FnMut(DragEndData)>>>,
-    pub on_cancel: Option<Rc<RefCell<dyn FnMut(DragCancelData)>>>,
-    pub has_started: Rc<dyn Fn([&PointerData; 2]) -> bool>,
+    pub on_start: Option<Handler<DragStartData>>,
+    pub on_update: Option<Handler<DragUpdateData>>,
+    pub on_end: Option<Handler<DragEndData>>,
+    pub on_cancel: Option<Handler<DragCancelData>>,
+    pub has_started: StartPredicate,
+    pub frame_mapping: Option<FrameMapping>,
 }
 
 impl Drag {
@@ -86,6 +94,17 @@ impl Drag {
         self.has_started = Rc::new(predicate);
         self
     }
+
+    /// Maps the local coordinates of the drag data differently than the
+    /// [`UseGesturesOptions::frame_mapping`] of the target, as for dragging SVG
+    /// nodes in [`FrameMapping::SvgParentUserSpace`] on a target that is
+    /// otherwise measured by its bounding rect.
+    ///
+    /// [`UseGesturesOptions::frame_mapping`]: crate::state::options::UseGesturesOptions::frame_mapping
+    pub fn frame_mapping(mut self, frame_mapping: FrameMapping) -> Self {
+        self.frame_mapping = Some(frame_mapping);
+        self
+    }
 }
 
 pub struct DragStartData {
@@ -111,6 +130,197 @@ impl Default for Drag {
             has_started: Rc::new(|[initial, current]| {
                 (current.client_coordinates() - initial.client_coordinates()).length() >= 5.0
             }),
+            frame_mapping: None,
         }
     }
 }
+
+impl DownPointerGesture for Drag {
+    const POINTERS: usize = 1;
+
+    fn has_started(&self, pointers: &[IncrementalOffsetPointer]) -> bool {
+        let [pointer] = pointers else {
+            return false;
+        };
+        (self.has_started)([&pointer.initial_data, &pointer.current_data])
+    }
+
+    fn frame_mapping(&self) -> Option<FrameMapping> {
+        self.frame_mapping
+    }
+
+    fn start(&self, pointers: Vec<InitialPointer>) {
+        if let (Some(handler), Ok([pointer])) = (&self.on_start, <[_; 1]>::try_from(pointers)) {
+            handler.borrow_mut()(DragStartData { pointer });
+        }
+    }
+
+    fn update(&self, pointers: Vec<IncrementalOffsetPointer>, _: Option<usize>) {
+        if let (Some(handler), Ok([pointer])) = (&self.on_update, <[_; 1]>::try_from(pointers)) {
+            handler.borrow_mut()(DragUpdateData { pointer });
+        }
+    }
+
+    fn end(&self, pointers: Vec<OffsetPointer>) {
+        if let (Some(handler), Ok([pointer])) = (&self.on_end, <[_; 1]>::try_from(pointers)) {
+            handler.borrow_mut()(DragEndData { pointer });
+        }
+    }
+
+    fn cancel(&self, pointers: Vec<OffsetPointer>) {
+        if let (Some(handler), Ok([pointer])) = (&self.on_cancel, <[_; 1]>::try_from(pointers)) {
+            handler.borrow_mut()(DragCancelData { pointer });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use dioxus::html::point_interaction::InteractionLocation;
+
+    use crate::{
+        state::{frame::LocalPoint, gestures::drag::Drag},
+        testing::{CallbackLog, GestureHarness, PointerSequence, SyntheticPointer},
+        use_gestures::Gestures,
+    };
+
+    #[test]
+    fn starts_once_the_threshold_is_reached() {
+        let log = CallbackLog::default();
+        let mut harness = GestureHarness::new(Gestures::default().drag(log.drag(Drag::default())));
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::touch(1))
+                .move_to(SyntheticPointer::touch(1).at(3.0, 0.0)),
+        );
+        log.assert_eq(&[]);
+
+        harness.play(
+            PointerSequence::default()
+                .move_to(SyntheticPointer::touch(1).at(6.0, 0.0))
+                .move_to(SyntheticPointer::touch(1).at(9.0, 0.0))
+                .up(1),
+        );
+        log.assert_eq(&["drag.start", "drag.update", "drag.end"]);
+    }
+
+    #[test]
+    fn ends_with_the_dragging_pointer_when_a_second_one_goes_down() {
+        let log = CallbackLog::default();
+        let end = Rc::new(Cell::new(None));
+        let mut harness =
+            GestureHarness::new(Gestures::default().drag(log.drag(Drag::default().on_end({
+                let end = Rc::clone(&end);
+                move |data| {
+                    end.set(Some((
+                        data.pointer.final_data.pointer_id(),
+                        data.pointer.final_data.client_coordinates().x,
+                    )))
+                }
+            }))));
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::touch(1))
+                .move_by_steps(1, (20.0, 0.0), 2)
+                .down(SyntheticPointer::touch(2).at(50.0, 50.0)),
+        );
+
+        log.assert_eq(&["drag.start", "drag.update", "drag.end"]);
+        assert_eq!(end.get(), Some((1, 20.0)));
+    }
+
+    #[test]
+    fn cancels_on_pointer_cancel() {
+        let log = CallbackLog::default();
+        let mut harness = GestureHarness::new(Gestures::default().drag(log.drag(Drag::default())));
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::mouse())
+                .move_by_steps(1, (10.0, 0.0), 2)
+                .cancel(1),
+        );
+        log.assert_order(&["drag.start", "drag.update", "drag.cancel"]);
+    }
+
+    #[test]
+    fn local_coordinates_keep_the_frame_measured_at_start() {
+        let local = Rc::new(RefCell::new(Vec::new()));
+        let mut harness = GestureHarness::new(
+            Gestures::default().drag(
+                Drag::default()
+                    .on_start({
+                        let local = Rc::clone(&local);
+                        move |data| local.borrow_mut().push(data.pointer.local_coordinates())
+                    })
+                    .on_update({
+                        let local = Rc::clone(&local);
+                        move |data| local.borrow_mut().push(data.pointer.local_coordinates())
+                    }),
+            ),
+        );
+
+        harness.play(
+            PointerSequence::default()
+                .down(
+                    SyntheticPointer::touch(1)
+                        .at(110.0, 60.0)
+                        .element_at(10.0, 10.0),
+                )
+                .move_to(
+                    SyntheticPointer::touch(1)
+                        .at(130.0, 70.0)
+                        .element_at(2.0, 2.0),
+                )
+                .move_to(
+                    SyntheticPointer::touch(1)
+                        .at(140.0, 90.0)
+                        .element_at(2.0, 2.0),
+                ),
+        );
+
+        assert_eq!(
+            *local.borrow(),
+            [LocalPoint::new(10.0, 10.0), LocalPoint::new(40.0, 40.0)]
+        );
+    }
+
+    #[test]
+    fn updates_fall_back_to_the_event_as_the_only_sample() {
+        let samples = Rc::new(RefCell::new(Vec::new()));
+        let mut harness =
+            GestureHarness::new(Gestures::default().drag(Drag::default().on_update({
+                let samples = Rc::clone(&samples);
+                move |data| {
+                    assert!(data.pointer.predicted_samples().is_empty());
+                    samples
+                        .borrow_mut()
+                        .extend(data.pointer.coalesced_samples());
+                }
+            })));
+
+        harness.play(
+            PointerSequence::default()
+                .down(
+                    SyntheticPointer::pen(1)
+                        .at(110.0, 60.0)
+                        .element_at(10.0, 10.0),
+                )
+                .move_to(SyntheticPointer::pen(1).at(120.0, 60.0).pressure(0.5))
+                .move_to(SyntheticPointer::pen(1).at(130.0, 60.0).pressure(0.75)),
+        );
+
+        let samples = samples.borrow();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].local_coordinates, LocalPoint::new(30.0, 10.0));
+        assert_eq!(samples[0].pressure, 0.75);
+        assert_eq!(samples[0].time_stamp, None);
+    }
+}
@@ -1,16 +1,66 @@
 use std::{cell::RefCell, rc::Rc};
 
-use dioxus::{html::PointerData, prelude::Event};
+use dioxus::{
+    html::{
+        geometry::ClientPoint,
+        input_data::MouseButton,
+        point_interaction::{InteractionLocation, PointerInteraction},
+        PointerData,
+    },
+    prelude::Event,
+};
 
 use crate::state::{
     events::PointerEventReceiver,
-    gestures::pointer::{IncrementalOffsetPointer, InitialPointer, OffsetPointer, PointerId},
+    frame::{FrameSource, TargetFrame},
+    gestures::{
+        pointer::{
+            IncrementalOffsetPointer, InitialPointer, OffsetPointer, PointerId, PointerSample,
+        },
+        Handler,
+    },
+    instrument,
+    recognizer::{GestureRecognizer, RecognizerPhase, TrackedPointer},
 };
 
 #[derive(Clone)]
 pub struct HoverGestureState {
     hover: Hover,
     pointers: Vec<HoverState>,
+    frames: FrameSource,
+    frame: Rc<TargetFrame>,
+    intent: IntentState,
+    long_presses: Vec<LongPressState>,
+    timeouts: Vec<(f64, u64)>,
+    next_token: u64,
+}
+
+/// A touch pointer that hovers once it has been held long enough, see
+/// [`TouchHover::LongPress`].
+#[derive(Clone)]
+struct LongPressState {
+    id: PointerId,
+    token: u64,
+    initial_state: Rc<PointerData>,
+    current_state: Rc<PointerData>,
+}
+
+#[derive(Clone, Default)]
+struct IntentState {
+    active: bool,
+    position: ClientPoint,
+    checked_position: ClientPoint,
+    /// The pending timeout, with its token. Replaced or cleared whenever the
+    /// hover starts or ends, so the timeouts set before are ignored.
+    timeout: Option<(u64, IntentTimeout)>,
+}
+
+#[derive(Clone, Copy)]
+enum IntentTimeout {
+    /// Checks whether the pointer has settled, see [`HoverIntent::enter_delay`].
+    Check,
+    /// Ends the intent, see [`HoverIntent::leave_grace`].
+    Leave,
 }
 
 #[derive(Clone)]
@@ -65,13 +115,20 @@ pub struct HoverPointerState {
 
 #[derive(Default, Clone)]
 pub struct Hover {
-    pub on_start: Option<Rc<RefCell<dyn FnMut(())>>>,
-    pub on_end: Option<Rc<RefCell<dyn FnMut(())>>>,
-    pub on_cancel: Option<Rc<RefCell<dyn FnMut(())>>>,
-    pub on_pointer_appear: Option<Rc<RefCell<dyn FnMut(HoverPointerAppearData)>>>,
-    pub on_pointer_update: Option<Rc<RefCell<dyn FnMut(HoverPointerUpdateData)>>>,
-    pub on_pointer_disappear: Option<Rc<RefCell<dyn FnMut(HoverPointerDisappearData)>>>,
-    pub on_pointer_cancel: Option<Rc<RefCell<dyn FnMut(HoverPointerCancelData)>>>,
+    pub on_start: Option<Handler<()>>,
+    pub on_end: Option<Handler<()>>,
+    pub on_cancel: Option<Handler<()>>,
+    pub on_intent_start: Option<Handler<()>>,
+    pub on_intent_end: Option<Handler<()>>,
+    pub intent: HoverIntent,
+    pub touch: TouchHover,
+    pub on_pointer_appear: Option<Handler<HoverPointerAppearData>>,
+    pub on_pointer_update: Option<Handler<HoverPointerUpdateData>>,
+    pub on_pointer_disappear: Option<Handler<HoverPointerDisappearData>>,
+    pub on_pointer_cancel: Option<Handler<HoverPointerCancelData>>,
+    pub on_pen_hover: Option<Handler<PenHoverData>>,
+    pub on_pen_proximity_enter: Option<Handler<PenHoverData>>,
+    pub on_pen_proximity_leave: Option<Handler<PenHoverData>>,
 }
 
 impl Hover {
@@ -90,6 +147,29 @@ impl Hover {
         self
     }
 
+    /// Called once the hover is intended, see [`HoverIntent`].
+    pub fn on_intent_start(mut self, handler: impl FnMut(()) + 'static) -> Self {
+        self.on_intent_start = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    /// Called once an intended hover has ended for the leave grace period, or
+    /// right away when it is cancelled.
+    pub fn on_intent_end(mut self, handler: impl FnMut(()) + 'static) -> Self {
+        self.on_intent_end = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    pub fn intent(mut self, intent: HoverIntent) -> Self {
+        self.intent = intent;
+        self
+    }
+
+    pub fn touch(mut self, touch: TouchHover) -> Self {
+        self.touch = touch;
+        self
+    }
+
     pub fn on_pointer_appear(
         mut self,
         handler: impl FnMut(HoverPointerAppearData) + 'static,
@@ -121,6 +201,127 @@ impl Hover {
         self.on_pointer_cancel = Some(Rc::new(RefCell::new(handler)));
         self
     }
+
+    /// Called whenever a pen above the target appears or moves without
+    /// touching the surface, as for a brush cursor preview.
+    pub fn on_pen_hover(mut self, handler: impl FnMut(PenHoverData) + 'static) -> Self {
+        self.on_pen_hover = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    /// Called when a pen comes into range above the target, or enters it
+    /// while in range.
+    pub fn on_pen_proximity_enter(mut self, handler: impl FnMut(PenHoverData) + 'static) -> Self {
+        self.on_pen_proximity_enter = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    /// Called when a pen goes out of range or leaves the target, with its last
+    /// position.
+    pub fn on_pen_proximity_leave(mut self, handler: impl FnMut(PenHoverData) + 'static) -> Self {
+        self.on_pen_proximity_leave = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+}
+
+/// When a hover counts as intended, for menus and tooltips that should not
+/// flicker as the pointer passes over the target.
+///
+/// The intent starts once the target has been hovered for the enter delay and
+/// the pointer has slowed down, and ends once the hover has ended for the
+/// leave grace period. A hover that starts again within the grace period keeps
+/// the intent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HoverIntent {
+    /// Milliseconds from the start of the hover to the first velocity test.
+    /// The tests are repeated at this interval until one passes.
+    pub enter_delay: f64,
+    pub leave_grace: f64,
+    /// The distance in pixels the pointer may move between two tests for the
+    /// intent to start. `None` starts the intent after the enter delay,
+    /// however fast the pointer moves.
+    pub sensitivity: Option<f64>,
+}
+
+impl HoverIntent {
+    pub fn enter_delay(mut self, milliseconds: f64) -> Self {
+        self.enter_delay = milliseconds;
+        self
+    }
+
+    pub fn leave_grace(mut self, milliseconds: f64) -> Self {
+        self.leave_grace = milliseconds;
+        self
+    }
+
+    pub fn sensitivity(mut self, pixels: Option<f64>) -> Self {
+        self.sensitivity = pixels;
+        self
+    }
+}
+
+impl Default for HoverIntent {
+    fn default() -> Self {
+        Self {
+            enter_delay: 100.0,
+            leave_grace: 100.0,
+            sensitivity: Some(7.0),
+        }
+    }
+}
+
+/// How touch pointers hover.
+///
+/// Browsers fire `pointerenter` before `pointerdown` and `pointerleave` after
+/// `pointerup` for touch, so by default every tap is a short hover.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TouchHover {
+    /// Touch pointers hover from `pointerenter` to `pointerleave`, like any
+    /// other pointer.
+    #[default]
+    Track,
+    /// Touch pointers never hover.
+    Ignore,
+    /// A touch pointer hovers once it has been held for `delay` milliseconds
+    /// without moving further than `tolerance` pixels, until it is lifted.
+    LongPress { delay: f64, tolerance: f64 },
+    /// A touch pointer hovers from `pointerenter` to `pointerup`, so the hover
+    /// ends as the finger is lifted even where `pointerleave` comes late.
+    EndOnUp,
+}
+
+/// A pen above the target, with the attributes that matter before it touches
+/// the surface. Pointer events do not report how far above the surface the
+/// pen is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PenHoverData {
+    pub pointer_id: PointerId,
+    /// The position, tilt and twist of the pen.
+    pub sample: PointerSample,
+    /// Whether the eraser end or button is active, reported as the sixth bit
+    /// of `buttons`, which dioxus decodes as [`MouseButton::Unknown`].
+    pub eraser: bool,
+    /// Whether the barrel button is held, reported as the secondary button.
+    pub barrel: bool,
+    /// Whether the pen touches the surface.
+    pub contact: bool,
+}
+
+impl PenHoverData {
+    /// The pen data of `data`, unless it is of another pointer type.
+    pub fn new(data: &PointerData, frame: &TargetFrame) -> Option<Self> {
+        if data.pointer_type() != "pen" {
+            return None;
+        }
+        let held_buttons = data.held_buttons();
+        Some(Self {
+            pointer_id: PointerId::from(data.pointer_id()),
+            sample: PointerSample::new(data, frame),
+            eraser: held_buttons.contains(MouseButton::Unknown),
+            barrel: held_buttons.contains(MouseButton::Secondary),
+            contact: held_buttons.contains(MouseButton::Primary) || data.pressure() > 0.0,
+        })
+    }
 }
 
 pub struct HoverPointerAppearData {
@@ -144,11 +345,20 @@ impl HoverGestureState {
         Self {
             hover,
             pointers: Vec::new(),
+            frames: FrameSource::default(),
+            frame: Rc::default(),
+            intent: IntentState::default(),
+            long_presses: Vec::new(),
+            timeouts: Vec::new(),
+            next_token: 0,
         }
     }
 
     fn add_hover_event(&mut self, pointer_data: Rc<PointerData>) {
         let is_first = self.pointers.is_empty();
+        if is_first {
+            self.frame = self.frames.measure(&pointer_data);
+        }
         self.pointers.push(HoverState {
             pointer: HoverPointerState {
                 id: PointerId::from(pointer_data.pointer_id()),
@@ -158,28 +368,71 @@ impl HoverGestureState {
         });
 
         if is_first {
+            instrument::event!(debug, "idle -> started");
+            instrument::event!(trace, callback = "start");
             if let Some(handler) = &self.hover.on_start {
                 handler.borrow_mut()(());
             }
+            self.start_intent(pointer_data.client_coordinates());
+        } else {
+            self.intent.position = pointer_data.client_coordinates();
         }
 
+        instrument::event!(trace, callback = "pointer_appear");
         if let Some(handler) = &self.hover.on_pointer_appear {
             handler.borrow_mut()(HoverPointerAppearData {
-                pointer: InitialPointer { data: pointer_data },
+                pointer: InitialPointer {
+                    data: Rc::clone(&pointer_data),
+                    frame: Rc::clone(&self.frame),
+                },
             });
         }
+
+        if let Some(pen) = PenHoverData::new(&pointer_data, &self.frame) {
+            instrument::event!(trace, callback = "pen_proximity_enter");
+            if let Some(handler) = &self.hover.on_pen_proximity_enter {
+                handler.borrow_mut()(pen);
+            }
+            self.pen_hover(pen);
+        }
+    }
+
+    fn pen_hover(&self, pen: PenHoverData) {
+        if pen.contact {
+            return;
+        }
+        instrument::event!(trace, callback = "pen_hover");
+        if let Some(handler) = &self.hover.on_pen_hover {
+            handler.borrow_mut()(pen);
+        }
+    }
+
+    fn pen_proximity_leave(&self, pointer_data: &PointerData) {
+        if let Some(pen) = PenHoverData::new(pointer_data, &self.frame) {
+            instrument::event!(trace, callback = "pen_proximity_leave");
+            if let Some(handler) = &self.hover.on_pen_proximity_leave {
+                handler.borrow_mut()(pen);
+            }
+        }
     }
 
     fn update_known_hover_event(&mut self, index: usize, pointer_data: Rc<PointerData>) {
         let initial_data = Rc::clone(&self.pointers[index].pointer.initial_state);
         let preceding_data = Rc::clone(&self.pointers[index].pointer.previous_state);
         self.pointers[index].pointer.previous_state = Rc::clone(&pointer_data);
+        self.intent.position = pointer_data.client_coordinates();
+        if let Some(pen) = PenHoverData::new(&pointer_data, &self.frame) {
+            self.pen_hover(pen);
+        }
+        instrument::event!(trace, callback = "pointer_update");
         if let Some(handler) = &self.hover.on_pointer_update {
             handler.borrow_mut()(HoverPointerUpdateData {
                 pointer: IncrementalOffsetPointer {
                     initial_data,
                     preceding_data,
                     current_data: pointer_data,
+                    held_back_data: Vec::new(),
+                    frame: Rc::clone(&self.frame),
                 },
             });
         }
@@ -188,41 +441,141 @@ impl HoverGestureState {
     fn remove_known_hover_event(&mut self, index: usize, pointer_data: Rc<PointerData>) {
         let hover = self.pointers.remove(index);
         let initial_data = hover.pointer.initial_state;
+        self.pen_proximity_leave(&pointer_data);
+        instrument::event!(trace, callback = "pointer_disappear");
         if let Some(handler) = &self.hover.on_pointer_disappear {
             handler.borrow_mut()(HoverPointerDisappearData {
                 pointer: OffsetPointer {
                     initial_data,
                     final_data: pointer_data,
+                    frame: Rc::clone(&self.frame),
                 },
             });
         }
 
         if self.pointers.is_empty() {
+            instrument::event!(debug, "started -> idle (end)");
+            instrument::event!(trace, callback = "end");
             if let Some(handler) = &self.hover.on_end {
                 handler.borrow_mut()(());
             }
+            self.end_intent();
         }
     }
 
     fn cancel_known_hover_event(&mut self, index: usize, pointer_data: Rc<PointerData>) {
         let hover = self.pointers.remove(index);
         let initial_data = hover.pointer.initial_state;
+        self.pen_proximity_leave(&pointer_data);
+        instrument::event!(trace, callback = "pointer_cancel");
         if let Some(handler) = &self.hover.on_pointer_cancel {
             handler.borrow_mut()(HoverPointerCancelData {
                 pointer: OffsetPointer {
                     initial_data,
                     final_data: pointer_data,
+                    frame: Rc::clone(&self.frame),
                 },
             });
         }
 
         if self.pointers.is_empty() {
+            instrument::event!(debug, "started -> idle (cancel)");
+            instrument::event!(trace, callback = "cancel");
             if let Some(handler) = &self.hover.on_cancel {
                 handler.borrow_mut()(())
             }
+            self.cancel_intent();
+        }
+    }
+
+    fn has_intent_handlers(&self) -> bool {
+        self.hover.on_intent_start.is_some() || self.hover.on_intent_end.is_some()
+    }
+
+    fn start_intent(&mut self, position: ClientPoint) {
+        if !self.has_intent_handlers() {
+            return;
+        }
+        self.intent.position = position;
+        self.intent.checked_position = position;
+        self.intent.timeout = None;
+        if self.intent.active {
+            instrument::event!(debug, "intent kept within the leave grace");
+            return;
+        }
+        self.check_intent(true);
+    }
+
+    /// Starts the intent once the pointer has moved no more than the
+    /// sensitivity since the preceding check, or checks again after the enter
+    /// delay. The first check is only delayed.
+    fn check_intent(&mut self, first: bool) {
+        let config = self.hover.intent;
+        let moved = (self.intent.position - self.intent.checked_position).length();
+        self.intent.checked_position = self.intent.position;
+        let start = match config.sensitivity {
+            _ if first && config.enter_delay > 0.0 => false,
+            Some(sensitivity) if moved > sensitivity => false,
+            _ => true,
+        };
+
+        if start {
+            self.intent.active = true;
+            instrument::event!(debug, "intent start");
+            instrument::event!(trace, callback = "intent_start");
+            if let Some(handler) = &self.hover.on_intent_start {
+                handler.borrow_mut()(());
+            }
+        } else {
+            let token = self.set_timeout(config.enter_delay);
+            self.intent.timeout = Some((token, IntentTimeout::Check));
         }
     }
 
+    fn end_intent(&mut self) {
+        if !self.has_intent_handlers() {
+            return;
+        }
+        self.intent.timeout = None;
+        if !self.intent.active {
+            return;
+        }
+        match self.hover.intent.leave_grace > 0.0 {
+            true => {
+                let token = self.set_timeout(self.hover.intent.leave_grace);
+                self.intent.timeout = Some((token, IntentTimeout::Leave));
+            }
+            false => self.finish_intent(),
+        }
+    }
+
+    fn cancel_intent(&mut self) {
+        if !self.has_intent_handlers() {
+            return;
+        }
+        self.intent.timeout = None;
+        self.finish_intent();
+    }
+
+    fn finish_intent(&mut self) {
+        if !self.intent.active {
+            return;
+        }
+        self.intent.active = false;
+        instrument::event!(debug, "intent end");
+        instrument::event!(trace, callback = "intent_end");
+        if let Some(handler) = &self.hover.on_intent_end {
+            handler.borrow_mut()(());
+        }
+    }
+
+    /// Requests a timeout after `delay` milliseconds, returning its token.
+    fn set_timeout(&mut self, delay: f64) -> u64 {
+        self.next_token += 1;
+        self.timeouts.push((delay, self.next_token));
+        self.next_token
+    }
+
     fn add_or_update(&mut self, event: &Event<PointerData>) {
         let pointer_data = event.data();
         let pointer_id = pointer_data.pointer_id();
@@ -240,6 +593,18 @@ impl HoverGestureState {
         };
     }
 
+    fn update(&mut self, event: &Event<PointerData>) {
+        let pointer_data = event.data();
+        let pointer_id = pointer_data.pointer_id();
+        let associated_hover_event = self
+            .pointers
+            .iter()
+            .position(|p| p.pointer.id.is_equal_i32(pointer_id));
+        if let Some(position) = associated_hover_event {
+            self.update_known_hover_event(position, pointer_data);
+        }
+    }
+
     fn remove(&mut self, event: &Event<PointerData>) {
         let pointer_data = event.data();
         let pointer_id = pointer_data.pointer_id();
@@ -247,12 +612,9 @@ impl HoverGestureState {
             .pointers
             .iter()
             .position(|p| p.pointer.id.is_equal_i32(pointer_id));
-        match associated_hover_event {
-            Some(position) => {
-                self.remove_known_hover_event(position, pointer_data);
-            }
-            None => {}
-        };
+        if let Some(position) = associated_hover_event {
+            self.remove_known_hover_event(position, pointer_data);
+        }
     }
 
     fn cancel(&mut self, event: &Event<PointerData>) {
@@ -262,12 +624,55 @@ impl HoverGestureState {
             .pointers
             .iter()
             .position(|p| p.pointer.id.is_equal_i32(pointer_id));
-        match associated_hover_event {
-            Some(position) => {
-                self.cancel_known_hover_event(position, pointer_data);
-            }
-            None => {}
+        if let Some(position) = associated_hover_event {
+            self.cancel_known_hover_event(position, pointer_data);
+        }
+    }
+}
+
+impl HoverGestureState {
+    /// The policy for the pointer of `event`, if it is a touch pointer.
+    fn touch_hover(&self, event: &Event<PointerData>) -> Option<TouchHover> {
+        (event.pointer_type() == "touch").then_some(self.hover.touch)
+    }
+
+    fn press(&mut self, event: &Event<PointerData>, delay: f64) {
+        let pointer_data = event.data();
+        let token = self.set_timeout(delay);
+        self.long_presses.push(LongPressState {
+            id: PointerId::from(pointer_data.pointer_id()),
+            token,
+            initial_state: Rc::clone(&pointer_data),
+            current_state: pointer_data,
+        });
+    }
+
+    /// Forgets the press of the pointer of `event` once it moved further than
+    /// `tolerance`.
+    fn move_press(&mut self, event: &Event<PointerData>, tolerance: f64) {
+        let pointer_data = event.data();
+        let pointer_id = pointer_data.pointer_id();
+        let Some(index) = self
+            .long_presses
+            .iter()
+            .position(|press| press.id.is_equal_i32(pointer_id))
+        else {
+            return;
         };
+        let press = &mut self.long_presses[index];
+        let distance =
+            (pointer_data.client_coordinates() - press.initial_state.client_coordinates()).length();
+        press.current_state = pointer_data;
+        if distance > tolerance {
+            instrument::event!(debug, pointer_id, "long press moved too far");
+            self.long_presses.remove(index);
+        }
+    }
+
+    fn release_press(&mut self, event: &Event<PointerData>) {
+        let pointer_id = event.pointer_id();
+        self.long_presses
+            .retain(|press| !press.id.is_equal_i32(pointer_id));
     }
 }
 
@@ -275,28 +680,485 @@ impl PointerEventReceiver<&Event<PointerData>> for HoverGestureState {
     fn pointer_over(&mut self, _: &Event<PointerData>) {}
 
     fn pointer_enter(&mut self, event: &Event<PointerData>) {
-        self.add_or_update(event);
+        match self.touch_hover(event) {
+            Some(TouchHover::Ignore | TouchHover::LongPress { .. }) => {}
+            _ => self.add_or_update(event),
+        }
     }
 
     fn pointer_down(&mut self, event: &Event<PointerData>) {
-        self.add_or_update(event);
+        match self.touch_hover(event) {
+            Some(TouchHover::Ignore) => {}
+            Some(TouchHover::LongPress { delay, .. }) => self.press(event, delay),
+            _ => self.add_or_update(event),
+        }
     }
 
     fn pointer_move(&mut self, event: &Event<PointerData>) {
-        self.add_or_update(event);
+        match self.touch_hover(event) {
+            Some(TouchHover::Ignore) => {}
+            Some(TouchHover::LongPress { tolerance, .. }) => {
+                self.move_press(event, tolerance);
+                self.update(event);
+            }
+            _ => self.add_or_update(event),
+        }
     }
 
     fn pointer_up(&mut self, event: &Event<PointerData>) {
-        self.add_or_update(event);
+        match self.touch_hover(event) {
+            Some(TouchHover::Ignore) => {}
+            Some(TouchHover::LongPress { .. }) => {
+                self.release_press(event);
+                self.remove(event);
+            }
+            Some(TouchHover::EndOnUp) => self.remove(event),
+            _ => self.add_or_update(event),
+        }
     }
 
     fn pointer_cancel(&mut self, event: &Event<PointerData>) {
+        self.release_press(event);
         self.cancel(event);
     }
 
     fn pointer_out(&mut self, _: &Event<PointerData>) {}
 
     fn pointer_leave(&mut self, event: &Event<PointerData>) {
+        self.release_press(event);
         self.remove(event);
     }
 }
+
+impl GestureRecognizer for HoverGestureState {
+    fn attach_frames(&mut self, frames: FrameSource) {
+        self.frames = frames;
+    }
+
+    fn take_timeouts(&mut self) -> Vec<(f64, u64)> {
+        std::mem::take(&mut self.timeouts)
+    }
+
+    fn timeout(&mut self, token: u64) {
+        if let Some((_, timeout)) = self.intent.timeout.filter(|(other, _)| *other == token) {
+            self.intent.timeout = None;
+            match timeout {
+                IntentTimeout::Check => self.check_intent(false),
+                IntentTimeout::Leave => self.finish_intent(),
+            }
+            return;
+        }
+        let Some(index) = self
+            .long_presses
+            .iter()
+            .position(|press| press.token == token)
+        else {
+            return;
+        };
+        let press = self.long_presses.remove(index);
+        instrument::event!(debug, pointer_id = press.id.0, "long press");
+        self.add_hover_event(press.current_state);
+    }
+
+    fn phase(&self) -> RecognizerPhase {
+        match self.pointers.is_empty() {
+            true => RecognizerPhase::Idle,
+            false => RecognizerPhase::Started,
+        }
+    }
+
+    fn tracked_pointers(&self) -> Vec<TrackedPointer> {
+        self.pointers
+            .iter()
+            .map(|hover| TrackedPointer {
+                id: hover.pointer.id,
+                initial_data: Rc::clone(&hover.pointer.initial_state),
+                current_data: Rc::clone(&hover.pointer.previous_state),
+                frame: Rc::clone(&self.frame),
+            })
+            .collect()
+    }
+
+    fn cancel(&mut self) {
+        self.long_presses.clear();
+        while let Some(hover) = self.pointers.last() {
+            let pointer_data = Rc::clone(&hover.pointer.previous_state);
+            self.cancel_known_hover_event(self.pointers.len() - 1, pointer_data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use dioxus::html::input_data::MouseButton;
+    use proptest::prelude::*;
+
+    use crate::{
+        state::{
+            gestures::hover::{Hover, HoverIntent, PenHoverData, TouchHover},
+            recognizer::RecognizerPhase,
+        },
+        testing::{
+            arbitrary_sequence, CallbackLog, GestureHarness, PointerEventKind, PointerSequence,
+            SyntheticPointer,
+        },
+        use_gestures::Gestures,
+    };
+
+    #[test]
+    fn follows_a_mouse_across_the_target() {
+        let log = CallbackLog::default();
+        let mut harness =
+            GestureHarness::new(Gestures::default().hover(log.hover(Hover::default())));
+
+        harness.play(
+            PointerSequence::default()
+                .event(PointerEventKind::Enter, SyntheticPointer::mouse())
+                .move_to(SyntheticPointer::mouse().at(5.0, 5.0))
+                .event(
+                    PointerEventKind::Leave,
+                    SyntheticPointer::mouse().at(5.0, 5.0),
+                ),
+        );
+
+        log.assert_eq(&[
+            "hover.start",
+            "hover.pointer_appear",
+            "hover.pointer_update",
+            "hover.pointer_disappear",
+            "hover.end",
+        ]);
+    }
+
+    #[test]
+    fn ends_once_the_last_pointer_leaves() {
+        let log = CallbackLog::default();
+        let mut harness =
+            GestureHarness::new(Gestures::default().hover(log.hover(Hover::default())));
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::touch(1))
+                .down(SyntheticPointer::touch(2))
+                .up(1)
+                .cancel(2),
+        );
+
+        assert_eq!(log.count("hover.start"), 1);
+        assert_eq!(log.count("hover.pointer_disappear"), 1);
+        assert_eq!(log.count("hover.pointer_cancel"), 1);
+        assert_eq!(log.entries().last(), Some(&"hover.cancel"));
+    }
+
+    #[test]
+    fn reports_a_pen_above_the_surface() {
+        let log = CallbackLog::default();
+        let samples = Rc::new(RefCell::new(Vec::new()));
+        let hover = Hover::default()
+            .on_pen_proximity_enter(log.record("pen.enter"))
+            .on_pen_proximity_leave(log.record("pen.leave"))
+            .on_pen_hover({
+                let samples = Rc::clone(&samples);
+                move |data: PenHoverData| samples.borrow_mut().push(data)
+            });
+        let mut harness = GestureHarness::new(Gestures::default().hover(hover));
+
+        let pen = SyntheticPointer::pen(1);
+        harness.play(
+            PointerSequence::default()
+                .event(PointerEventKind::Enter, pen.clone().at(10.0, 10.0))
+                .move_to(pen.clone().at(12.0, 10.0).tilt(30, -15).twist(90))
+                .move_to(
+                    pen.clone()
+                        .at(12.0, 10.0)
+                        .buttons(None, MouseButton::Secondary | MouseButton::Unknown),
+                )
+                .down(
+                    pen.clone()
+                        .at(12.0, 10.0)
+                        .pressure(0.5)
+                        .buttons(Some(MouseButton::Primary), MouseButton::Primary.into()),
+                )
+                .up(1),
+        );
+
+        log.assert_eq(&["pen.enter", "pen.leave"]);
+        // The contact of the pen is no hover.
+        let samples = samples.borrow();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(
+            (
+                samples[1].sample.tilt_x,
+                samples[1].sample.tilt_y,
+                samples[1].sample.twist
+            ),
+            (30, -15, 90)
+        );
+        assert!(samples[2].barrel && samples[2].eraser && !samples[2].contact);
+    }
+
+    #[test]
+    fn ignores_other_pointers_for_pen_callbacks() {
+        let log = CallbackLog::default();
+        let mut harness =
+            GestureHarness::new(Gestures::default().hover(log.hover(Hover::default())));
+
+        harness.play(
+            PointerSequence::default()
+                .event(PointerEventKind::Enter, SyntheticPointer::mouse())
+                .event(PointerEventKind::Leave, SyntheticPointer::mouse()),
+        );
+
+        assert_eq!(log.count("hover.pen_proximity_enter"), 0);
+        assert_eq!(log.count("hover.pen_proximity_leave"), 0);
+    }
+
+    fn touch_harness(log: &CallbackLog, touch: TouchHover) -> GestureHarness {
+        GestureHarness::new(Gestures::default().hover(log.hover(Hover::default().touch(touch))))
+    }
+
+    #[test]
+    fn ignores_touch_pointers() {
+        let log = CallbackLog::default();
+        let mut harness = touch_harness(&log, TouchHover::Ignore);
+
+        harness.play(PointerSequence::drag(
+            SyntheticPointer::touch(1),
+            (20.0, 0.0),
+            2,
+        ));
+        log.assert_eq(&[]);
+
+        harness.play(PointerSequence::drag(
+            SyntheticPointer::pen(2),
+            (20.0, 0.0),
+            2,
+        ));
+        assert_eq!(log.count("hover.start"), 1);
+    }
+
+    #[test]
+    fn ends_touch_hover_on_pointer_up() {
+        let log = CallbackLog::default();
+        let mut harness = touch_harness(&log, TouchHover::EndOnUp);
+
+        harness
+            .play(PointerSequence::default().down(SyntheticPointer::touch(1)))
+            .send(PointerEventKind::Up, &SyntheticPointer::touch(1));
+        assert_eq!(harness.phase("hover"), Some(RecognizerPhase::Idle));
+        log.assert_eq(&[
+            "hover.start",
+            "hover.pointer_appear",
+            "hover.pointer_update",
+            "hover.pointer_disappear",
+            "hover.end",
+        ]);
+
+        harness.play(PointerSequence::default().up(1));
+        log.assert_eq(&[]);
+    }
+
+    #[test]
+    fn hovers_with_a_long_press() {
+        let log = CallbackLog::default();
+        let mut harness = touch_harness(
+            &log,
+            TouchHover::LongPress {
+                delay: 500.0,
+                tolerance: 10.0,
+            },
+        );
+
+        harness
+            .play(PointerSequence::default().down(SyntheticPointer::touch(1)))
+            .advance(499.0);
+        log.assert_eq(&[]);
+        harness.advance(1.0);
+        log.assert_eq(&["hover.start", "hover.pointer_appear"]);
+
+        harness.play(
+            PointerSequence::default()
+                .move_to(SyntheticPointer::touch(1).at(30.0, 0.0))
+                .up(1),
+        );
+        log.assert_eq(&[
+            "hover.pointer_update",
+            "hover.pointer_disappear",
+            "hover.end",
+        ]);
+
+        // Moving too far or lifting early is no long press.
+        harness
+            .play(
+                PointerSequence::default()
+                    .down(SyntheticPointer::touch(2))
+                    .move_to(SyntheticPointer::touch(2).at(20.0, 0.0)),
+            )
+            .advance(500.0)
+            .play(PointerSequence::default().up(2))
+            .play(
+                PointerSequence::default()
+                    .down(SyntheticPointer::touch(3))
+                    .up(3),
+            )
+            .advance(500.0);
+        log.assert_eq(&[]);
+    }
+
+    fn intent_harness(log: &CallbackLog) -> GestureHarness {
+        GestureHarness::new(
+            Gestures::default().hover(
+                Hover::default()
+                    .intent(HoverIntent::default())
+                    .on_intent_start(log.record("intent.start"))
+                    .on_intent_end(log.record("intent.end")),
+            ),
+        )
+    }
+
+    fn enter(x: f64, y: f64) -> PointerSequence {
+        PointerSequence::default()
+            .event(PointerEventKind::Enter, SyntheticPointer::mouse().at(x, y))
+    }
+
+    fn leave() -> PointerSequence {
+        PointerSequence::default().event(PointerEventKind::Leave, SyntheticPointer::mouse())
+    }
+
+    #[test]
+    fn logging_sets_no_intent_handlers() {
+        let hover = CallbackLog::default().hover(Hover::default());
+        assert!(hover.on_start.is_some());
+        assert!(hover.on_intent_start.is_none() && hover.on_intent_end.is_none());
+    }
+
+    #[test]
+    fn intent_starts_after_the_enter_delay() {
+        let log = CallbackLog::default();
+        let mut harness = intent_harness(&log);
+
+        harness.play(enter(0.0, 0.0)).advance(99.0);
+        log.assert_eq(&[]);
+        harness.advance(1.0);
+        log.assert_eq(&["intent.start"]);
+
+        // Passing over the target is not intended.
+        harness.play(leave()).advance(100.0);
+        log.assert_eq(&["intent.end"]);
+        harness
+            .play(enter(0.0, 0.0))
+            .advance(50.0)
+            .play(leave())
+            .advance(500.0);
+        log.assert_eq(&[]);
+    }
+
+    #[test]
+    fn intent_waits_for_the_pointer_to_slow_down() {
+        let log = CallbackLog::default();
+        let mut harness = intent_harness(&log);
+
+        harness
+            .play(enter(0.0, 0.0))
+            .play(PointerSequence::default().move_to(SyntheticPointer::mouse().at(50.0, 0.0)))
+            .advance(100.0);
+        log.assert_eq(&[]);
+
+        harness
+            .play(PointerSequence::default().move_to(SyntheticPointer::mouse().at(55.0, 0.0)))
+            .advance(100.0);
+        log.assert_eq(&["intent.start"]);
+    }
+
+    #[test]
+    fn intent_is_kept_within_the_leave_grace() {
+        let log = CallbackLog::default();
+        let mut harness = intent_harness(&log);
+
+        harness.play(enter(0.0, 0.0)).advance(100.0);
+        harness
+            .play(leave())
+            .advance(50.0)
+            .play(enter(0.0, 0.0))
+            .advance(500.0);
+        log.assert_eq(&["intent.start"]);
+
+        harness.play(leave()).advance(99.0);
+        log.assert_eq(&[]);
+        harness.advance(1.0);
+        log.assert_eq(&["intent.end"]);
+    }
+
+    /// Checks that pointers appear before they are updated and disappear, and
+    /// that the hover starts with the first and ends with the last pointer.
+    fn check_hover(calls: &[(&'static str, Option<i32>)]) -> Result<(), String> {
+        let mut pointers = Vec::new();
+        let mut started = false;
+        for (name, id) in calls {
+            match (*name, id) {
+                ("hover.start", None) if !started && pointers.is_empty() => started = true,
+                ("hover.end" | "hover.cancel", None) if started && pointers.is_empty() => {
+                    started = false
+                }
+                ("hover.pointer_appear", Some(id)) if started && !pointers.contains(id) => {
+                    pointers.push(*id)
+                }
+                ("hover.pointer_update", Some(id)) if pointers.contains(id) => {}
+                ("hover.pointer_disappear" | "hover.pointer_cancel", Some(id))
+                    if pointers.contains(id) =>
+                {
+                    pointers.retain(|pointer| pointer != id)
+                }
+                _ => return Err(format!("{name} {id:?} with pointers {pointers:?}")),
+            }
+        }
+        match started {
+            true => Err(format!("hover never ended, pointers {pointers:?}")),
+            false => Ok(()),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn pointers_appear_and_disappear_in_order(sequence in arbitrary_sequence()) {
+            let calls = Rc::new(RefCell::new(Vec::new()));
+            let record = |name: &'static str| {
+                let calls = Rc::clone(&calls);
+                move |id: Option<i32>| calls.borrow_mut().push((name, id))
+            };
+            let start = record("hover.start");
+            let end = record("hover.end");
+            let cancel = record("hover.cancel");
+            let appear = record("hover.pointer_appear");
+            let update = record("hover.pointer_update");
+            let disappear = record("hover.pointer_disappear");
+            let pointer_cancel = record("hover.pointer_cancel");
+            let mut harness = GestureHarness::new(
+                Gestures::default().hover(
+                    Hover::default()
+                        .on_start(move |_| start(None))
+                        .on_end(move |_| end(None))
+                        .on_cancel(move |_| cancel(None))
+                        .on_pointer_appear(move |d| appear(Some(d.pointer.data.pointer_id())))
+                        .on_pointer_update(move |d| {
+                            update(Some(d.pointer.current_data.pointer_id()))
+                        })
+                        .on_pointer_disappear(move |d| {
+                            disappear(Some(d.pointer.final_data.pointer_id()))
+                        })
+                        .on_pointer_cancel(move |d| {
+                            pointer_cancel(Some(d.pointer.final_data.pointer_id()))
+                        }),
+                ),
+            );
+
+            harness.play(sequence);
+
+            prop_assert_eq!(check_hover(&calls.borrow()), Ok(()));
+            prop_assert!(harness.tracked_pointers("hover").is_empty());
+            prop_assert_eq!(harness.phase("hover"), Some(RecognizerPhase::Idle));
+        }
+    }
+}
@@ -1,23 +1,28 @@
 use std::{cell::RefCell, rc::Rc};
 
-
 use dioxus::html::point_interaction::{InteractionElementOffset, InteractionLocation};
-use dioxus::{
-    html::{
-        geometry::{
-            euclid::{Angle, Point2D, Vector2D},
-            ClientPoint, ClientSpace, ElementPoint, PagePoint, ScreenPoint,
-        },
-        PointerData,
+use dioxus::html::{
+    geometry::{
+        euclid::{Angle, Point2D, Vector2D},
+        ClientPoint, ClientSpace, ElementPoint, PagePoint, ScreenPoint,
     },
+    PointerData,
 };
 
-use crate::state::gestures::pointer::{IncrementalOffsetPointer, InitialPointer, OffsetPointer};
+use crate::state::{
+    frame::{FrameMapping, LocalPoint, LocalVector, TargetFrame},
+    gestures::{
+        down_pointer::DownPointerGesture,
+        pointer::{IncrementalOffsetPointer, InitialPointer, OffsetPointer},
+        Handler, PairStartPredicate,
+    },
+};
 
 /// ```rust
-/// use dioxus::{html::geometry::euclid::Angle, prelude::*};
+/// use dioxus::prelude::*;
 /// use dioxus_gestures::{
 ///     state::gestures::pinch::Pinch,
+///     transform::GestureTransform,
 ///     use_gestures::{use_gestures, Gestures},
 /// };
 ///
@@ -26,18 +31,14 @@ use crate::state::gestures::pointer::{IncrementalOffsetPointer, InitialPointer,
 ///
 /// #[component]
 /// pub fn PinchExample() -> Element {
-///     let mut rotation = use_signal(|| Angle::<f64>::default());
-///     let mut scale = use_signal(|| 1.0);
+///     let mut transform = use_signal(|| GestureTransform::default().min_scale(0.25).max_scale(4.0));
 ///     let mut text = use_signal(|| UNDRAGGED_TEXT);
 ///
 ///     let gestures = use_gestures(
 ///         Gestures::default().pinch(
 ///             Pinch::default()
 ///                 .on_start(move |_| text.set(DRAGGED_TEXT))
-///                 .on_update(move |data| {
-///                     scale.set(scale() * data.delta_distance_scale());
-///                     rotation.set(rotation() + data.delta_angle());
-///                 })
+///                 .on_update(move |data| transform.write().apply_pinch(&data))
 ///                 .on_end(move |_| text.set(UNDRAGGED_TEXT))
 ///                 .on_cancel(move |_| text.set(UNDRAGGED_TEXT)),
 ///         ),
@@ -45,26 +46,35 @@ use crate::state::gestures::pointer::{IncrementalOffsetPointer, InitialPointer,
 ///
 ///     rsx! {
 ///         div {
-///         class: "target pinch",
-///         style: format!("
+///         style: "
 ///             user-select: none;
 ///             touch-action: none;
-///             position: relative;
-///             transform: scale({}) rotate({}rad);
-///         ", scale(), rotation().get()),
+///             align-self: flex-start;
+///             margin: 1em;
+///         ",
 ///         ..gestures.event_handlers(),
-///             "{text}"
+///             div {
+///             class: "target pinch",
+///             style: format!("
+///                 margin: 0;
+///                 transform-origin: 0 0;
+///                 transform: {};
+///             ", transform.read().to_css_matrix()),
+///                 "{text}"
+///             }
 ///         }
 ///     }
 /// }
 /// ```
 #[derive(Clone)]
 pub struct Pinch {
-    pub on_start: Option<Rc<RefCell<dyn FnMut(PinchStartData)>>>,
-    pub on_update: Option<Rc<RefCell<dyn FnMut(PinchUpdateData)>>>,
-    pub on_end: Option<Rc<RefCell<dyn FnMut(PinchEndData)>>>,
-    pub on_cancel: Option<Rc<RefCell<dyn FnMut(PinchCancelData)>>>,
-    pub has_started: Rc<dyn Fn([&PointerData; 2], [&PointerData; 2]) -> bool>,
+    pub on_start: Option<Handler<PinchStartData>>,
+    pub on_update: Option<Handler<PinchUpdateData>>,
+    pub on_end: Option<Handler<PinchEndData>>,
+    pub on_cancel: Option<Handler<PinchCancelData>>,
+    pub has_started: PairStartPredicate,
+    pub joint_updates: bool,
+    pub frame_mapping: Option<FrameMapping>,
 }
 
 impl Pinch {
@@ -95,6 +105,23 @@ impl Pinch {
         self.has_started = Rc::new(predicate);
         self
     }
+
+    /// Holds back updates until both pointers have moved, so every update
+    /// carries the new position of both fingers. If one of them stays still,
+    /// the update is delivered on the next animation frame.
+    pub fn joint_updates(mut self, joint_updates: bool) -> Self {
+        self.joint_updates = joint_updates;
+        self
+    }
+
+    /// Maps the local coordinates of the pinch data differently than the
+    /// [`UseGesturesOptions::frame_mapping`] of the target.
+    ///
+    /// [`UseGesturesOptions::frame_mapping`]: crate::state::options::UseGesturesOptions::frame_mapping
+    pub fn frame_mapping(mut self, frame_mapping: FrameMapping) -> Self {
+        self.frame_mapping = Some(frame_mapping);
+        self
+    }
 }
 
 impl Default for Pinch {
@@ -109,6 +136,61 @@ impl Default for Pinch {
                     || (b_current.client_coordinates() - b_initial.client_coordinates()).length()
                         >= 5.0
             }),
+            joint_updates: false,
+            frame_mapping: None,
+        }
+    }
+}
+
+impl DownPointerGesture for Pinch {
+    const POINTERS: usize = 2;
+
+    fn has_started(&self, pointers: &[IncrementalOffsetPointer]) -> bool {
+        let [a, b] = pointers else {
+            return false;
+        };
+        (self.has_started)(
+            [&a.initial_data, &a.current_data],
+            [&b.initial_data, &b.current_data],
+        )
+    }
+
+    fn start(&self, pointers: Vec<InitialPointer>) {
+        if let (Some(handler), Ok(pointers)) = (&self.on_start, pointers.try_into()) {
+            handler.borrow_mut()(PinchStartData { pointers });
+        }
+    }
+
+    fn update(&self, pointers: Vec<IncrementalOffsetPointer>, updated_pointer: Option<usize>) {
+        if let (Some(handler), Ok(pointers)) = (&self.on_update, pointers.try_into()) {
+            handler.borrow_mut()(PinchUpdateData {
+                pointers,
+                updated_pointer: match updated_pointer {
+                    Some(0) => PinchUpdatedPointer::First,
+                    Some(_) => PinchUpdatedPointer::Second,
+                    None => PinchUpdatedPointer::Both,
+                },
+            });
+        }
+    }
+
+    fn joint_updates(&self) -> bool {
+        self.joint_updates
+    }
+
+    fn frame_mapping(&self) -> Option<FrameMapping> {
+        self.frame_mapping
+    }
+
+    fn end(&self, pointers: Vec<OffsetPointer>) {
+        if let (Some(handler), Ok(pointers)) = (&self.on_end, pointers.try_into()) {
+            handler.borrow_mut()(PinchEndData { pointers });
+        }
+    }
+
+    fn cancel(&self, pointers: Vec<OffsetPointer>) {
+        if let (Some(handler), Ok(pointers)) = (&self.on_cancel, pointers.try_into()) {
+            handler.borrow_mut()(PinchCancelData { pointers });
         }
     }
 }
@@ -134,6 +216,14 @@ impl PinchStartData {
         PinchData::element_center(&self.pointers[0].data, &self.pointers[1].data)
     }
 
+    pub fn local_center(&self) -> LocalPoint {
+        PinchData::local_center(
+            &self.pointers[0].frame,
+            &self.pointers[0].data,
+            &self.pointers[1].data,
+        )
+    }
+
     pub fn distance(&self) -> f64 {
         PinchData::distance(&self.pointers[0].data, &self.pointers[1].data)
     }
@@ -148,9 +238,17 @@ pub struct PinchUpdateData {
     pub updated_pointer: PinchUpdatedPointer,
 }
 
+/// The pointers that moved in an update. More variants may be added, so
+/// matches need a wildcard arm.
+#[non_exhaustive]
 pub enum PinchUpdatedPointer {
     First,
     Second,
+    /// Both pointers moved, see [`UpdateDelivery::PerFrame`] and
+    /// [`Pinch::joint_updates`].
+    ///
+    /// [`UpdateDelivery::PerFrame`]: crate::state::delivery::UpdateDelivery::PerFrame
+    Both,
 }
 
 impl PinchUpdateData {
@@ -182,6 +280,14 @@ impl PinchUpdateData {
         )
     }
 
+    pub fn start_local_center(&self) -> LocalPoint {
+        PinchData::local_center(
+            &self.pointers[0].frame,
+            &self.pointers[0].initial_data,
+            &self.pointers[1].initial_data,
+        )
+    }
+
     pub fn start_distance(&self) -> f64 {
         PinchData::distance(
             &self.pointers[0].initial_data,
@@ -224,6 +330,14 @@ impl PinchUpdateData {
         )
     }
 
+    pub fn preceding_local_center(&self) -> LocalPoint {
+        PinchData::local_center(
+            &self.pointers[0].frame,
+            &self.pointers[0].preceding_data,
+            &self.pointers[1].preceding_data,
+        )
+    }
+
     pub fn preceding_distance(&self) -> f64 {
         PinchData::distance(
             &self.pointers[0].preceding_data,
@@ -242,6 +356,10 @@ impl PinchUpdateData {
         self.current_client_center() - self.preceding_client_center()
     }
 
+    pub fn delta_local_movement(&self) -> LocalVector {
+        self.current_local_center() - self.preceding_local_center()
+    }
+
     pub fn delta_distance(&self) -> f64 {
         self.current_distance() - self.preceding_distance()
     }
@@ -282,6 +400,14 @@ impl PinchUpdateData {
         )
     }
 
+    pub fn current_local_center(&self) -> LocalPoint {
+        PinchData::local_center(
+            &self.pointers[0].frame,
+            &self.pointers[0].current_data,
+            &self.pointers[1].current_data,
+        )
+    }
+
     pub fn current_distance(&self) -> f64 {
         PinchData::distance(
             &self.pointers[0].current_data,
@@ -300,6 +426,10 @@ impl PinchUpdateData {
         self.current_client_center() - self.start_client_center()
     }
 
+    pub fn offset_local_movement(&self) -> LocalVector {
+        self.current_local_center() - self.start_local_center()
+    }
+
     pub fn offset_distance(&self) -> f64 {
         self.current_distance() - self.start_distance()
     }
@@ -346,6 +476,14 @@ impl PinchEndData {
         )
     }
 
+    pub fn start_local_center(&self) -> LocalPoint {
+        PinchData::local_center(
+            &self.pointers[0].frame,
+            &self.pointers[0].initial_data,
+            &self.pointers[1].initial_data,
+        )
+    }
+
     pub fn start_distance(&self) -> f64 {
         PinchData::distance(
             &self.pointers[0].initial_data,
@@ -376,6 +514,14 @@ impl PinchEndData {
         PinchData::element_center(&self.pointers[0].final_data, &self.pointers[1].final_data)
     }
 
+    pub fn end_local_center(&self) -> LocalPoint {
+        PinchData::local_center(
+            &self.pointers[0].frame,
+            &self.pointers[0].final_data,
+            &self.pointers[1].final_data,
+        )
+    }
+
     pub fn end_distance(&self) -> f64 {
         PinchData::distance(&self.pointers[0].final_data, &self.pointers[1].final_data)
     }
@@ -388,6 +534,10 @@ impl PinchEndData {
         self.end_client_center() - self.start_client_center()
     }
 
+    pub fn offset_local_movement(&self) -> LocalVector {
+        self.end_local_center() - self.start_local_center()
+    }
+
     pub fn offset_distance(&self) -> f64 {
         self.end_distance() - self.start_distance()
     }
@@ -434,6 +584,14 @@ impl PinchCancelData {
         )
     }
 
+    pub fn start_local_center(&self) -> LocalPoint {
+        PinchData::local_center(
+            &self.pointers[0].frame,
+            &self.pointers[0].initial_data,
+            &self.pointers[1].initial_data,
+        )
+    }
+
     pub fn start_distance(&self) -> f64 {
         PinchData::distance(
             &self.pointers[0].initial_data,
@@ -464,6 +622,14 @@ impl PinchCancelData {
         PinchData::element_center(&self.pointers[0].final_data, &self.pointers[1].final_data)
     }
 
+    pub fn end_local_center(&self) -> LocalPoint {
+        PinchData::local_center(
+            &self.pointers[0].frame,
+            &self.pointers[0].final_data,
+            &self.pointers[1].final_data,
+        )
+    }
+
     pub fn end_distance(&self) -> f64 {
         PinchData::distance(&self.pointers[0].final_data, &self.pointers[1].final_data)
     }
@@ -476,6 +642,10 @@ impl PinchCancelData {
         self.end_client_center() - self.start_client_center()
     }
 
+    pub fn offset_local_movement(&self) -> LocalVector {
+        self.end_local_center() - self.start_local_center()
+    }
+
     pub fn offset_distance(&self) -> f64 {
         self.end_distance() - self.start_distance()
     }
@@ -489,38 +659,225 @@ impl PinchCancelData {
     }
 }
 
-struct PinchData;
+pub(crate) struct PinchData;
 
 impl PinchData {
-    fn client_center(a: &PointerData, b: &PointerData) -> ClientPoint {
+    pub(crate) fn client_center(a: &PointerData, b: &PointerData) -> ClientPoint {
         Self::center(a.client_coordinates(), b.client_coordinates())
     }
 
-    fn screen_center(a: &PointerData, b: &PointerData) -> ScreenPoint {
+    pub(crate) fn screen_center(a: &PointerData, b: &PointerData) -> ScreenPoint {
         Self::center(a.screen_coordinates(), b.screen_coordinates())
     }
 
-    fn page_center(a: &PointerData, b: &PointerData) -> PagePoint {
+    pub(crate) fn page_center(a: &PointerData, b: &PointerData) -> PagePoint {
         Self::center(a.page_coordinates(), b.page_coordinates())
     }
 
-    fn element_center(a: &PointerData, b: &PointerData) -> ElementPoint {
+    pub(crate) fn element_center(a: &PointerData, b: &PointerData) -> ElementPoint {
         Self::center(a.element_coordinates(), b.element_coordinates())
     }
 
-    fn center<T>(a: Point2D<f64, T>, b: Point2D<f64, T>) -> Point2D<f64, T> {
+    pub(crate) fn local_center(
+        frame: &TargetFrame,
+        a: &PointerData,
+        b: &PointerData,
+    ) -> LocalPoint {
+        frame.local_point(Self::client_center(a, b))
+    }
+
+    pub(crate) fn center<T>(a: Point2D<f64, T>, b: Point2D<f64, T>) -> Point2D<f64, T> {
         a.lerp(b, 0.5)
     }
 
-    fn distance(a: &PointerData, b: &PointerData) -> f64 {
+    pub(crate) fn distance(a: &PointerData, b: &PointerData) -> f64 {
         a.client_coordinates().distance_to(b.client_coordinates())
     }
 
-    fn angle(a: &PointerData, b: &PointerData) -> Angle<f64> {
+    pub(crate) fn angle(a: &PointerData, b: &PointerData) -> Angle<f64> {
         (b.client_coordinates() - a.client_coordinates()).angle_from_x_axis()
     }
 
-    fn angle_difference(a: Angle<f64>, b: Angle<f64>) -> Angle<f64> {
+    pub(crate) fn angle_difference(a: Angle<f64>, b: Angle<f64>) -> Angle<f64> {
         (a - b).signed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    };
+
+    use crate::{
+        state::{
+            delivery::UpdateDelivery,
+            gestures::{
+                drag::Drag,
+                pinch::{Pinch, PinchUpdatedPointer},
+            },
+            options::UseGesturesOptions,
+        },
+        testing::{
+            CallbackLog, GestureHarness, PointerEventKind, PointerSequence, SyntheticPointer,
+        },
+        use_gestures::Gestures,
+    };
+
+    #[test]
+    fn reports_the_scale_of_the_pinch() {
+        let log = CallbackLog::default();
+        let scale = Rc::new(Cell::new(0.0));
+        let mut harness = GestureHarness::new(Gestures::default().pinch(log.pinch(
+            Pinch::default().on_end({
+                let scale = Rc::clone(&scale);
+                move |data| scale.set(data.offset_distance_scale())
+            }),
+        )));
+
+        harness.play(PointerSequence::pinch((100.0, 100.0), 50.0, 150.0, 4));
+
+        log.assert_order(&["pinch.start", "pinch.update", "pinch.end"]);
+        assert!((scale.get() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn takes_over_from_drag() {
+        let log = CallbackLog::default();
+        let mut harness = GestureHarness::new(
+            Gestures::default()
+                .drag(log.drag(Drag::default()))
+                .pinch(log.pinch(Pinch::default())),
+        );
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::touch(1))
+                .move_by_steps(1, (10.0, 0.0), 2)
+                .down(SyntheticPointer::touch(2).at(50.0, 0.0))
+                .move_by_steps(2, (60.0, 0.0), 2)
+                .up(2)
+                .move_by_steps(1, (0.0, 0.0), 2)
+                .up(1),
+        );
+
+        log.assert_order(&[
+            "drag.start",
+            "drag.update",
+            "drag.end",
+            "pinch.start",
+            "pinch.update",
+            "pinch.end",
+            "drag.start",
+            "drag.update",
+            "drag.end",
+        ]);
+    }
+
+    #[test]
+    fn restarts_once_a_third_pointer_is_released() {
+        let log = CallbackLog::default();
+        let mut harness =
+            GestureHarness::new(Gestures::default().pinch(log.pinch(Pinch::default())));
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::touch(1))
+                .down(SyntheticPointer::touch(2).at(50.0, 0.0))
+                .move_by_steps(2, (60.0, 0.0), 2)
+                .down(SyntheticPointer::touch(3).at(100.0, 0.0)),
+        );
+        log.assert_order(&["pinch.start", "pinch.update", "pinch.end"]);
+
+        harness.play(
+            PointerSequence::default()
+                .event(
+                    PointerEventKind::Up,
+                    SyntheticPointer::touch(3).at(100.0, 0.0),
+                )
+                .move_to(SyntheticPointer::touch(2).at(70.0, 0.0))
+                .move_to(SyntheticPointer::touch(2).at(80.0, 0.0))
+                .event(PointerEventKind::Up, SyntheticPointer::touch(1)),
+        );
+        log.assert_order(&["pinch.start", "pinch.update", "pinch.end"]);
+    }
+
+    #[test]
+    fn combines_the_updates_of_a_frame() {
+        let log = CallbackLog::default();
+        let updates = Rc::new(RefCell::new(Vec::new()));
+        let mut harness = GestureHarness::new(
+            Gestures::default()
+                .options(UseGesturesOptions::default().update_delivery(UpdateDelivery::PerFrame))
+                .pinch(
+                    log.pinch(Pinch::default().has_started(|_, _| true).on_update({
+                        let updates = Rc::clone(&updates);
+                        move |data| {
+                            let both = matches!(data.updated_pointer, PinchUpdatedPointer::Both);
+                            updates.borrow_mut().push((both, data.delta_distance()));
+                        }
+                    })),
+                ),
+        );
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::touch(1))
+                .down(SyntheticPointer::touch(2).at(50.0, 0.0))
+                .move_to(SyntheticPointer::touch(1).at(-10.0, 0.0))
+                .move_to(SyntheticPointer::touch(2).at(60.0, 0.0))
+                .move_to(SyntheticPointer::touch(2).at(70.0, 0.0)),
+        );
+        log.assert_eq(&["pinch.start"]);
+
+        harness.frame();
+        harness.frame();
+        assert_eq!(*updates.borrow(), [(true, 30.0)]);
+
+        // A pending update is delivered before the pinch ends.
+        harness.play(
+            PointerSequence::default()
+                .move_to(SyntheticPointer::touch(2).at(80.0, 0.0))
+                .up(2),
+        );
+        log.assert_eq(&["pinch.update", "pinch.update", "pinch.end"]);
+        assert_eq!(updates.borrow()[1], (false, 10.0));
+    }
+
+    #[test]
+    fn joint_updates_wait_for_both_pointers() {
+        let updates = Rc::new(RefCell::new(Vec::new()));
+        let mut harness = GestureHarness::new(
+            Gestures::default().pinch(
+                Pinch::default()
+                    .has_started(|_, _| true)
+                    .joint_updates(true)
+                    .on_update({
+                        let updates = Rc::clone(&updates);
+                        move |data| {
+                            let both = matches!(data.updated_pointer, PinchUpdatedPointer::Both);
+                            updates
+                                .borrow_mut()
+                                .push((both, data.delta_distance_scale()));
+                        }
+                    }),
+            ),
+        );
+
+        harness.play(PointerSequence::pinch((100.0, 100.0), 50.0, 150.0, 2));
+        assert_eq!(*updates.borrow(), [(true, 2.0), (true, 1.5)]);
+
+        // A pointer that stays still holds the update back until the next frame.
+        updates.borrow_mut().clear();
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::touch(1))
+                .down(SyntheticPointer::touch(2).at(50.0, 0.0))
+                .move_to(SyntheticPointer::touch(2).at(100.0, 0.0)),
+        );
+        assert!(updates.borrow().is_empty());
+        harness.frame();
+        assert_eq!(*updates.borrow(), [(false, 2.0)]);
+    }
+}
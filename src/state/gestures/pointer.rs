@@ -5,12 +5,14 @@ use dioxus::prelude::Modifiers;
 use dioxus::html::point_interaction::{InteractionLocation, ModifiersInteraction, PointerInteraction};
 use dioxus::{
     html::{
-        geometry::{euclid::Vector2D, ClientSpace},
+        geometry::{euclid::Vector2D, ClientPoint, ClientSpace},
         input_data::MouseButtonSet,
         PointerData,
     },
 };
 
+use crate::state::frame::{LocalPoint, LocalVector, TargetFrame};
+
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct PointerId(pub i32);
 
@@ -36,6 +38,13 @@ pub struct IncrementalOffsetPointer {
     pub initial_data: Rc<PointerData>,
     pub preceding_data: Rc<PointerData>,
     pub current_data: Rc<PointerData>,
+    /// The moves between the preceding and the current one, oldest first,
+    /// whose updates were held back and combined into this one, see
+    /// [`UpdateDelivery::PerFrame`].
+    ///
+    /// [`UpdateDelivery::PerFrame`]: crate::state::delivery::UpdateDelivery::PerFrame
+    pub held_back_data: Vec<Rc<PointerData>>,
+    pub frame: Rc<TargetFrame>,
 }
 
 impl IncrementalOffsetPointer {
@@ -84,6 +93,56 @@ impl IncrementalOffsetPointer {
     }
 }
 
+impl IncrementalOffsetPointer {
+    pub fn local_coordinates(&self) -> LocalPoint {
+        self.frame
+            .local_point(self.current_data.client_coordinates())
+    }
+
+    pub fn delta_local_movement(&self) -> LocalVector {
+        self.frame.local_vector(self.delta_movement())
+    }
+
+    pub fn offset_local_movement(&self) -> LocalVector {
+        self.frame.local_vector(self.offset_movement())
+    }
+}
+
+/// The samples of a pointer move, for drawing and latency hiding.
+///
+/// Browsers coalesce the samples of high-rate pens and touch screens into a
+/// single `pointermove` event, and may predict the samples that follow it.
+/// Both are only read when asked for, and need the `web` feature. Without it,
+/// the event itself is the only coalesced sample and nothing is predicted.
+impl IncrementalOffsetPointer {
+    /// The samples since the preceding update, ending with the current one,
+    /// including those of the moves that were held back.
+    pub fn coalesced_samples(&self) -> Vec<PointerSample> {
+        self.held_back_data
+            .iter()
+            .chain([&self.current_data])
+            .flat_map(|data| Self::event_samples(data, &self.frame))
+            .collect()
+    }
+
+    fn event_samples(data: &PointerData, frame: &TargetFrame) -> Vec<PointerSample> {
+        #[cfg(feature = "web")]
+        if let Some(samples) = web::coalesced_samples(data, frame) {
+            return samples;
+        }
+        vec![PointerSample::new(data, frame)]
+    }
+
+    /// The samples the browser expects to follow the current one.
+    pub fn predicted_samples(&self) -> Vec<PointerSample> {
+        #[cfg(feature = "web")]
+        if let Some(samples) = web::predicted_samples(&self.current_data, &self.frame) {
+            return samples;
+        }
+        Vec::new()
+    }
+}
+
 impl IncrementalOffsetPointer {
     pub fn offset_movement(&self) -> Vector2D<f64, ClientSpace> {
         PointerDataDifference::coordinates(&self.current_data, &self.initial_data)
@@ -133,6 +192,17 @@ impl IncrementalOffsetPointer {
 pub struct OffsetPointer {
     pub initial_data: Rc<PointerData>,
     pub final_data: Rc<PointerData>,
+    pub frame: Rc<TargetFrame>,
+}
+
+impl OffsetPointer {
+    pub fn local_coordinates(&self) -> LocalPoint {
+        self.frame.local_point(self.final_data.client_coordinates())
+    }
+
+    pub fn offset_local_movement(&self) -> LocalVector {
+        self.frame.local_vector(self.offset_movement())
+    }
 }
 
 impl OffsetPointer {
@@ -183,6 +253,119 @@ impl OffsetPointer {
 
 pub struct InitialPointer {
     pub data: Rc<PointerData>,
+    pub frame: Rc<TargetFrame>,
+}
+
+impl InitialPointer {
+    pub fn local_coordinates(&self) -> LocalPoint {
+        self.frame.local_point(self.data.client_coordinates())
+    }
+}
+
+/// A single position of a pointer, with the attributes it was sampled with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointerSample {
+    pub client_coordinates: ClientPoint,
+    pub local_coordinates: LocalPoint,
+    pub width: f64,
+    pub height: f64,
+    pub pressure: f32,
+    pub tangential_pressure: f32,
+    pub tilt_x: i32,
+    pub tilt_y: i32,
+    pub twist: i32,
+    /// The time the sample was taken, in milliseconds, if known.
+    pub time_stamp: Option<f64>,
+}
+
+impl PointerSample {
+    pub fn new(data: &PointerData, frame: &TargetFrame) -> Self {
+        #[cfg(feature = "web")]
+        if let Some(event) = data.downcast::<web_sys::PointerEvent>() {
+            return web::sample(event, frame);
+        }
+        Self {
+            client_coordinates: data.client_coordinates(),
+            local_coordinates: frame.local_point(data.client_coordinates()),
+            width: data.width(),
+            height: data.height(),
+            pressure: data.pressure(),
+            tangential_pressure: data.tangential_pressure(),
+            tilt_x: data.tilt_x(),
+            tilt_y: data.tilt_y(),
+            twist: data.twist(),
+            time_stamp: None,
+        }
+    }
+}
+
+#[cfg(feature = "web")]
+mod web {
+    use dioxus::html::{geometry::ClientPoint, PointerData};
+    use web_sys::{
+        js_sys::{Array, Function, Reflect},
+        wasm_bindgen::{JsCast, JsValue},
+        PointerEvent,
+    };
+
+    use super::PointerSample;
+    use crate::state::frame::TargetFrame;
+
+    pub(super) fn sample(event: &PointerEvent, frame: &TargetFrame) -> PointerSample {
+        let client_coordinates = ClientPoint::new(event.client_x().into(), event.client_y().into());
+        PointerSample {
+            client_coordinates,
+            local_coordinates: frame.local_point(client_coordinates),
+            width: event.width() as _,
+            height: event.height() as _,
+            pressure: event.pressure(),
+            tangential_pressure: event.tangential_pressure(),
+            tilt_x: event.tilt_x(),
+            tilt_y: event.tilt_y(),
+            twist: event.twist(),
+            time_stamp: Some(event.time_stamp()),
+        }
+    }
+
+    pub(super) fn coalesced_samples(
+        data: &PointerData,
+        frame: &TargetFrame,
+    ) -> Option<Vec<PointerSample>> {
+        let event = data.downcast::<PointerEvent>()?;
+        if !has_method(event, "getCoalescedEvents") {
+            return None;
+        }
+        let samples = samples(event.get_coalesced_events(), frame);
+        // Events that are not coalesced, like `pointerdown`, have no samples.
+        (!samples.is_empty()).then_some(samples)
+    }
+
+    /// `getPredictedEvents` is not part of `web-sys` yet, so it is called by
+    /// name.
+    pub(super) fn predicted_samples(
+        data: &PointerData,
+        frame: &TargetFrame,
+    ) -> Option<Vec<PointerSample>> {
+        let event = data.downcast::<PointerEvent>()?;
+        let method = Reflect::get(event, &JsValue::from_str("getPredictedEvents"))
+            .ok()?
+            .dyn_into::<Function>()
+            .ok()?;
+        let events = method.call0(event).ok()?.dyn_into::<Array>().ok()?;
+        Some(samples(events, frame))
+    }
+
+    fn has_method(event: &PointerEvent, name: &str) -> bool {
+        Reflect::get(event, &JsValue::from_str(name)).is_ok_and(|method| method.is_function())
+    }
+
+    fn samples(events: Array, frame: &TargetFrame) -> Vec<PointerSample> {
+        events
+            .iter()
+            .filter_map(|event| event.dyn_into::<PointerEvent>().ok())
+            .map(|event| sample(&event, frame))
+            .collect()
+    }
 }
 
 struct PointerDataDifference;
@@ -229,6 +412,6 @@ impl PointerDataDifference {
     }
 
     fn held_buttons(a: &PointerData, b: &PointerData) -> MouseButtonSet {
-        a.held_buttons().symmetrical_difference(b.held_buttons())
+        a.held_buttons().symmetric_difference(b.held_buttons())
     }
 }
@@ -0,0 +1,208 @@
+use dioxus::html::geometry::euclid::Angle;
+
+use crate::state::{
+    frame::FrameMapping,
+    gestures::{
+        down_pointer::DownPointerGesture,
+        pinch::{Pinch, PinchCancelData, PinchData, PinchEndData, PinchStartData, PinchUpdateData},
+        pointer::{IncrementalOffsetPointer, InitialPointer, OffsetPointer},
+    },
+};
+
+/// A two pointer gesture that starts once the pointers have turned by the
+/// threshold angle, whatever their distance and movement. It reports the same
+/// data as a [`Pinch`].
+///
+/// Rotate and [`Scale`] are exclusive by default, so whichever crosses its
+/// threshold first locks the gesture to rotating or scaling. Making them
+/// simultaneous with [`Arbitration::simultaneous_with`] lets both start. Both
+/// are simultaneous with [`Pinch`].
+///
+/// ```rust
+/// use dioxus::{html::geometry::euclid::Angle, prelude::*};
+/// use dioxus_gestures::{
+///     state::gestures::{rotate::Rotate, scale::Scale},
+///     use_gestures::{use_gestures, Gestures},
+/// };
+///
+/// #[component]
+/// pub fn Map() -> Element {
+///     let mut rotation = use_signal(|| 0.0);
+///     let mut zoom = use_signal(|| 1.0);
+///
+///     let gestures = use_gestures(
+///         Gestures::default()
+///             .rotate(
+///                 Rotate::default()
+///                     .threshold(Angle::degrees(15.0))
+///                     .on_update(move |data| *rotation.write() += data.delta_angle().to_degrees()),
+///             )
+///             .scale(Scale::default().on_update(move |data| *zoom.write() *= data.delta_distance_scale())),
+///     );
+///
+///     rsx! {
+///         div {
+///             style: "touch-action: none; transform: rotate({rotation}deg) scale({zoom});",
+///             ..gestures.event_handlers(),
+///             "Map"
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Scale`]: crate::state::gestures::scale::Scale
+/// [`Arbitration::simultaneous_with`]: crate::state::arbitration::Arbitration::simultaneous_with
+#[derive(Clone)]
+pub struct Rotate {
+    pub pinch: Pinch,
+    pub threshold: Angle<f64>,
+}
+
+impl Rotate {
+    pub fn on_start(mut self, handler: impl FnMut(PinchStartData) + 'static) -> Self {
+        self.pinch = self.pinch.on_start(handler);
+        self
+    }
+
+    pub fn on_update(mut self, handler: impl FnMut(PinchUpdateData) + 'static) -> Self {
+        self.pinch = self.pinch.on_update(handler);
+        self
+    }
+
+    pub fn on_end(mut self, handler: impl FnMut(PinchEndData) + 'static) -> Self {
+        self.pinch = self.pinch.on_end(handler);
+        self
+    }
+
+    pub fn on_cancel(mut self, handler: impl FnMut(PinchCancelData) + 'static) -> Self {
+        self.pinch = self.pinch.on_cancel(handler);
+        self
+    }
+
+    pub fn joint_updates(mut self, joint_updates: bool) -> Self {
+        self.pinch = self.pinch.joint_updates(joint_updates);
+        self
+    }
+
+    pub fn frame_mapping(mut self, frame_mapping: FrameMapping) -> Self {
+        self.pinch = self.pinch.frame_mapping(frame_mapping);
+        self
+    }
+
+    pub fn threshold(mut self, threshold: Angle<f64>) -> Self {
+        self.threshold = threshold;
+        self
+    }
+}
+
+impl Default for Rotate {
+    fn default() -> Self {
+        Self {
+            pinch: Pinch::default(),
+            threshold: Angle::degrees(10.0),
+        }
+    }
+}
+
+impl DownPointerGesture for Rotate {
+    const POINTERS: usize = 2;
+
+    fn has_started(&self, pointers: &[IncrementalOffsetPointer]) -> bool {
+        let [a, b] = pointers else {
+            return false;
+        };
+        let angle = PinchData::angle_difference(
+            PinchData::angle(&a.current_data, &b.current_data),
+            PinchData::angle(&a.initial_data, &b.initial_data),
+        );
+        angle.radians.abs() >= self.threshold.radians
+    }
+
+    fn start(&self, pointers: Vec<InitialPointer>) {
+        self.pinch.start(pointers);
+    }
+
+    fn update(&self, pointers: Vec<IncrementalOffsetPointer>, updated_pointer: Option<usize>) {
+        self.pinch.update(pointers, updated_pointer);
+    }
+
+    fn joint_updates(&self) -> bool {
+        self.pinch.joint_updates
+    }
+
+    fn frame_mapping(&self) -> Option<FrameMapping> {
+        self.pinch.frame_mapping
+    }
+
+    fn end(&self, pointers: Vec<OffsetPointer>) {
+        self.pinch.end(pointers);
+    }
+
+    fn cancel(&self, pointers: Vec<OffsetPointer>) {
+        self.pinch.cancel(pointers);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        state::{
+            arbitration::Arbitration,
+            gestures::{rotate::Rotate, scale::Scale},
+        },
+        testing::{CallbackLog, GestureHarness, PointerSequence, SyntheticPointer},
+        use_gestures::Gestures,
+    };
+
+    fn gestures(log: &CallbackLog) -> Gestures {
+        Gestures::default()
+            .rotate(Rotate::default().on_start(log.record("rotate.start")))
+            .scale(Scale::default().on_start(log.record("scale.start")))
+    }
+
+    /// Two pointers 100 apart, of which the second moves to `(x, y)`.
+    fn turn_to(x: f64, y: f64) -> PointerSequence {
+        PointerSequence::default()
+            .down(SyntheticPointer::touch(1))
+            .down(SyntheticPointer::touch(2).at(100.0, 0.0))
+            .move_to(SyntheticPointer::touch(2).at(x, y))
+            .up(2)
+            .up(1)
+    }
+
+    #[test]
+    fn locks_to_the_first_threshold_crossed() {
+        let log = CallbackLog::default();
+        let mut harness = GestureHarness::new(gestures(&log));
+
+        // 8 degrees and a scale of 1.05 cross neither threshold.
+        harness.play(turn_to(104.0, 14.8));
+        log.assert_eq(&[]);
+
+        // 20 degrees, then a scale of 1.2.
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::touch(1))
+                .down(SyntheticPointer::touch(2).at(100.0, 0.0))
+                .move_to(SyntheticPointer::touch(2).at(94.0, 34.2))
+                .move_to(SyntheticPointer::touch(2).at(112.8, 41.0))
+                .up(2)
+                .up(1),
+        );
+        log.assert_eq(&["rotate.start"]);
+
+        harness.play(turn_to(120.0, 0.0));
+        log.assert_eq(&["scale.start"]);
+    }
+
+    #[test]
+    fn simultaneous_rotate_and_scale_both_start() {
+        let log = CallbackLog::default();
+        let mut harness = GestureHarness::new(
+            gestures(&log).arbitration(Arbitration::default().simultaneous_with("rotate", "scale")),
+        );
+
+        harness.play(turn_to(112.8, 41.0));
+        log.assert_eq(&["rotate.start", "scale.start"]);
+    }
+}
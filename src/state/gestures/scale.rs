@@ -0,0 +1,106 @@
+use crate::state::{
+    frame::FrameMapping,
+    gestures::{
+        down_pointer::DownPointerGesture,
+        pinch::{Pinch, PinchCancelData, PinchData, PinchEndData, PinchStartData, PinchUpdateData},
+        pointer::{IncrementalOffsetPointer, InitialPointer, OffsetPointer},
+    },
+};
+
+/// A two pointer gesture that starts once the distance of the pointers has
+/// changed by the threshold ratio, whatever their angle and movement. It
+/// reports the same data as a [`Pinch`].
+///
+/// See [`Rotate`] for how the two are arbitrated.
+///
+/// [`Rotate`]: crate::state::gestures::rotate::Rotate
+#[derive(Clone)]
+pub struct Scale {
+    pub pinch: Pinch,
+    /// The ratio of the current to the initial distance, or its inverse,
+    /// that starts the gesture.
+    pub threshold: f64,
+}
+
+impl Scale {
+    pub fn on_start(mut self, handler: impl FnMut(PinchStartData) + 'static) -> Self {
+        self.pinch = self.pinch.on_start(handler);
+        self
+    }
+
+    pub fn on_update(mut self, handler: impl FnMut(PinchUpdateData) + 'static) -> Self {
+        self.pinch = self.pinch.on_update(handler);
+        self
+    }
+
+    pub fn on_end(mut self, handler: impl FnMut(PinchEndData) + 'static) -> Self {
+        self.pinch = self.pinch.on_end(handler);
+        self
+    }
+
+    pub fn on_cancel(mut self, handler: impl FnMut(PinchCancelData) + 'static) -> Self {
+        self.pinch = self.pinch.on_cancel(handler);
+        self
+    }
+
+    pub fn joint_updates(mut self, joint_updates: bool) -> Self {
+        self.pinch = self.pinch.joint_updates(joint_updates);
+        self
+    }
+
+    pub fn frame_mapping(mut self, frame_mapping: FrameMapping) -> Self {
+        self.pinch = self.pinch.frame_mapping(frame_mapping);
+        self
+    }
+
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+}
+
+impl Default for Scale {
+    fn default() -> Self {
+        Self {
+            pinch: Pinch::default(),
+            threshold: 1.1,
+        }
+    }
+}
+
+impl DownPointerGesture for Scale {
+    const POINTERS: usize = 2;
+
+    fn has_started(&self, pointers: &[IncrementalOffsetPointer]) -> bool {
+        let [a, b] = pointers else {
+            return false;
+        };
+        let scale = PinchData::distance(&a.current_data, &b.current_data)
+            / PinchData::distance(&a.initial_data, &b.initial_data);
+        scale.ln().abs() >= self.threshold.ln().abs()
+    }
+
+    fn start(&self, pointers: Vec<InitialPointer>) {
+        self.pinch.start(pointers);
+    }
+
+    fn update(&self, pointers: Vec<IncrementalOffsetPointer>, updated_pointer: Option<usize>) {
+        self.pinch.update(pointers, updated_pointer);
+    }
+
+    fn joint_updates(&self) -> bool {
+        self.pinch.joint_updates
+    }
+
+    fn frame_mapping(&self) -> Option<FrameMapping> {
+        self.pinch.frame_mapping
+    }
+
+    fn end(&self, pointers: Vec<OffsetPointer>) {
+        self.pinch.end(pointers);
+    }
+
+    fn cancel(&self, pointers: Vec<OffsetPointer>) {
+        self.pinch.cancel(pointers);
+    }
+}
@@ -0,0 +1,569 @@
+use std::{cell::RefCell, fmt::Write, rc::Rc};
+
+use dioxus::html::PointerData;
+
+use crate::state::{
+    frame::{LocalPoint, TargetFrame},
+    gestures::{
+        down_pointer::DownPointerGesture,
+        pointer::{IncrementalOffsetPointer, InitialPointer, OffsetPointer, PointerSample},
+        Handler, StartPredicate,
+    },
+};
+
+/// Records every point of a single pointer drawing on the target, for pen and
+/// touch drawing.
+///
+/// The points are in the local coordinates of the target, and include the
+/// coalesced samples of every move with the `web` feature. Once the stroke
+/// ends, they are simplified and smoothed as configured and passed to
+/// `on_end`, which can also render them as SVG path data.
+///
+/// A stroke starts as soon as its pointer goes down, so it takes precedence
+/// over a [`Drag`] of the same target, unless they are made simultaneous with
+/// an [`Arbitration`].
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_gestures::{
+///     state::gestures::stroke::{Smoothing, Stroke},
+///     use_gestures::{use_gestures, Gestures},
+/// };
+///
+/// #[component]
+/// pub fn Sketch() -> Element {
+///     let mut paths = use_signal(Vec::<String>::new);
+///
+///     let gestures = use_gestures(
+///         Gestures::default().stroke(
+///             Stroke::default()
+///                 .simplify(0.5)
+///                 .smoothing(Smoothing::CatmullRom { segments: 4 })
+///                 .on_end(move |data| paths.write().push(data.svg_path())),
+///         ),
+///     );
+///
+///     rsx! {
+///         svg {
+///             width: 400,
+///             height: 300,
+///             style: "touch-action: none;",
+///             ..gestures.event_handlers(),
+///             for path in paths.iter() {
+///                 path { d: "{path}", fill: "none", stroke: "black" }
+///             }
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Drag`]: crate::state::gestures::drag::Drag
+/// [`Arbitration`]: crate::state::arbitration::Arbitration
+#[derive(Clone)]
+pub struct Stroke {
+    pub on_start: Option<Handler<StrokeStartData>>,
+    pub on_update: Option<Handler<StrokeUpdateData>>,
+    pub on_end: Option<Handler<StrokeEndData>>,
+    pub on_cancel: Option<Handler<StrokeCancelData>>,
+    pub has_started: StartPredicate,
+    pub smoothing: Smoothing,
+    /// The tolerance of the Ramer–Douglas–Peucker simplification, in local
+    /// units. Zero keeps every point.
+    pub simplify: f64,
+    points: Rc<RefCell<Vec<StrokePoint>>>,
+}
+
+impl Stroke {
+    pub fn on_start(mut self, handler: impl FnMut(StrokeStartData) + 'static) -> Self {
+        self.on_start = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    pub fn on_update(mut self, handler: impl FnMut(StrokeUpdateData) + 'static) -> Self {
+        self.on_update = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    pub fn on_end(mut self, handler: impl FnMut(StrokeEndData) + 'static) -> Self {
+        self.on_end = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    pub fn on_cancel(mut self, handler: impl FnMut(StrokeCancelData) + 'static) -> Self {
+        self.on_cancel = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+
+    pub fn has_started(mut self, predicate: impl Fn([&PointerData; 2]) -> bool + 'static) -> Self {
+        self.has_started = Rc::new(predicate);
+        self
+    }
+
+    pub fn smoothing(mut self, smoothing: Smoothing) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+
+    pub fn simplify(mut self, tolerance: f64) -> Self {
+        self.simplify = tolerance;
+        self
+    }
+}
+
+/// How the recorded points of a stroke are smoothed once it ends.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Smoothing {
+    #[default]
+    None,
+    /// Interpolates `segments` points between every two recorded points along
+    /// a centripetal Catmull-Rom spline through them.
+    CatmullRom { segments: usize },
+}
+
+/// A point of a stroke, with the pen attributes it was recorded with.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrokePoint {
+    pub position: LocalPoint,
+    pub pressure: f32,
+    pub tangential_pressure: f32,
+    pub tilt_x: i32,
+    pub tilt_y: i32,
+    pub twist: i32,
+}
+
+impl StrokePoint {
+    pub fn new(frame: &TargetFrame, data: &PointerData) -> Self {
+        Self::from(PointerSample::new(data, frame))
+    }
+
+    fn lerp(&self, other: &Self, t: f64) -> Self {
+        let lerp_f32 = |a: f32, b: f32| a + (b - a) * t as f32;
+        let lerp_i32 = |a: i32, b: i32| (a as f64 + (b - a) as f64 * t).round() as i32;
+        Self {
+            position: self.position.lerp(other.position, t),
+            pressure: lerp_f32(self.pressure, other.pressure),
+            tangential_pressure: lerp_f32(self.tangential_pressure, other.tangential_pressure),
+            tilt_x: lerp_i32(self.tilt_x, other.tilt_x),
+            tilt_y: lerp_i32(self.tilt_y, other.tilt_y),
+            twist: lerp_i32(self.twist, other.twist),
+        }
+    }
+}
+
+impl From<PointerSample> for StrokePoint {
+    fn from(sample: PointerSample) -> Self {
+        Self {
+            position: sample.local_coordinates,
+            pressure: sample.pressure,
+            tangential_pressure: sample.tangential_pressure,
+            tilt_x: sample.tilt_x,
+            tilt_y: sample.tilt_y,
+            twist: sample.twist,
+        }
+    }
+}
+
+pub struct StrokeStartData {
+    pub pointer: InitialPointer,
+    pub point: StrokePoint,
+}
+pub struct StrokeUpdateData {
+    pub pointer: IncrementalOffsetPointer,
+    /// The points added by the update, one for each coalesced sample of the
+    /// moves it combines.
+    pub points: Vec<StrokePoint>,
+}
+pub struct StrokeEndData {
+    pub pointer: OffsetPointer,
+    /// The simplified and smoothed points of the stroke.
+    pub points: Vec<StrokePoint>,
+}
+pub struct StrokeCancelData {
+    pub pointer: OffsetPointer,
+    /// The recorded points of the stroke, as they are.
+    pub points: Vec<StrokePoint>,
+}
+
+impl StrokeEndData {
+    pub fn svg_path(&self) -> String {
+        svg_path(&self.points)
+    }
+}
+
+impl Default for Stroke {
+    fn default() -> Self {
+        Self {
+            on_start: Default::default(),
+            on_update: Default::default(),
+            on_end: Default::default(),
+            on_cancel: Default::default(),
+            has_started: Rc::new(|_| true),
+            smoothing: Smoothing::default(),
+            simplify: 0.0,
+            points: Default::default(),
+        }
+    }
+}
+
+impl DownPointerGesture for Stroke {
+    const POINTERS: usize = 1;
+
+    fn has_started(&self, pointers: &[IncrementalOffsetPointer]) -> bool {
+        let [pointer] = pointers else {
+            return false;
+        };
+        (self.has_started)([&pointer.initial_data, &pointer.current_data])
+    }
+
+    fn start(&self, pointers: Vec<InitialPointer>) {
+        let Ok([pointer]) = <[_; 1]>::try_from(pointers) else {
+            return;
+        };
+        let point = StrokePoint::new(&pointer.frame, &pointer.data);
+        *self.points.borrow_mut() = vec![point];
+        if let Some(handler) = &self.on_start {
+            handler.borrow_mut()(StrokeStartData { pointer, point });
+        }
+    }
+
+    fn update(&self, pointers: Vec<IncrementalOffsetPointer>, _: Option<usize>) {
+        let Ok([pointer]) = <[_; 1]>::try_from(pointers) else {
+            return;
+        };
+        let points = pointer
+            .coalesced_samples()
+            .into_iter()
+            .map(StrokePoint::from)
+            .collect::<Vec<_>>();
+        self.points.borrow_mut().extend(&points);
+        if let Some(handler) = &self.on_update {
+            handler.borrow_mut()(StrokeUpdateData { pointer, points });
+        }
+    }
+
+    fn end(&self, pointers: Vec<OffsetPointer>) {
+        let Ok([pointer]) = <[_; 1]>::try_from(pointers) else {
+            return;
+        };
+        let mut points = self.take_points(&pointer);
+        if self.simplify > 0.0 {
+            points = simplify(&points, self.simplify);
+        }
+        if let Smoothing::CatmullRom { segments } = self.smoothing {
+            points = catmull_rom(&points, segments);
+        }
+        if let Some(handler) = &self.on_end {
+            handler.borrow_mut()(StrokeEndData { pointer, points });
+        }
+    }
+
+    fn cancel(&self, pointers: Vec<OffsetPointer>) {
+        let Ok([pointer]) = <[_; 1]>::try_from(pointers) else {
+            return;
+        };
+        let points = self.take_points(&pointer);
+        if let Some(handler) = &self.on_cancel {
+            handler.borrow_mut()(StrokeCancelData { pointer, points });
+        }
+    }
+}
+
+impl Stroke {
+    /// Takes the recorded points, along with the final position of the
+    /// pointer if it moved since the last update.
+    fn take_points(&self, pointer: &OffsetPointer) -> Vec<StrokePoint> {
+        let mut points = std::mem::take(&mut *self.points.borrow_mut());
+        let last = StrokePoint::new(&pointer.frame, &pointer.final_data);
+        if points.last().map(|point| point.position) != Some(last.position) {
+            points.push(last);
+        }
+        points
+    }
+}
+
+/// Renders points as SVG path data, made of a move to the first point and
+/// lines to the others.
+pub fn svg_path(points: &[StrokePoint]) -> String {
+    let mut path = String::new();
+    for (index, point) in points.iter().enumerate() {
+        let command = if index == 0 { 'M' } else { 'L' };
+        let separator = if index == 0 { "" } else { " " };
+        let _ = write!(
+            path,
+            "{separator}{command}{} {}",
+            point.position.x, point.position.y
+        );
+    }
+    path
+}
+
+/// Ramer–Douglas–Peucker simplification, keeping the points that deviate more
+/// than `tolerance` from the line through their neighbours.
+pub fn simplify(points: &[StrokePoint], tolerance: f64) -> Vec<StrokePoint> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    let mut ranges = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = ranges.pop() {
+        let (a, b) = (points[first].position, points[last].position);
+        let farthest = (first + 1..last)
+            .map(|index| (index, segment_distance(points[index].position, a, b)))
+            .max_by(|x, y| x.1.total_cmp(&y.1));
+        if let Some((index, distance)) = farthest {
+            if distance > tolerance {
+                keep[index] = true;
+                ranges.push((first, index));
+                ranges.push((index, last));
+            }
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(point, keep)| keep.then_some(*point))
+        .collect()
+}
+
+fn segment_distance(point: LocalPoint, a: LocalPoint, b: LocalPoint) -> f64 {
+    let segment = b - a;
+    let length = segment.square_length();
+    if length == 0.0 {
+        return (point - a).length();
+    }
+    let t = ((point - a).dot(segment) / length).clamp(0.0, 1.0);
+    (point - a.lerp(b, t)).length()
+}
+
+/// Interpolates `segments` points between every two points along a
+/// centripetal Catmull-Rom spline, which passes through all of them. The pen
+/// attributes are interpolated linearly.
+pub fn catmull_rom(points: &[StrokePoint], segments: usize) -> Vec<StrokePoint> {
+    if points.len() < 3 || segments == 0 {
+        return points.to_vec();
+    }
+    let mut smoothed = Vec::with_capacity((points.len() - 1) * (segments + 1) + 1);
+    for index in 0..points.len() - 1 {
+        let p1 = &points[index];
+        let p2 = &points[index + 1];
+        let p0 = points.get(index.wrapping_sub(1)).unwrap_or(p1);
+        let p3 = points.get(index + 2).unwrap_or(p2);
+
+        smoothed.push(*p1);
+        for step in 1..=segments {
+            let t = step as f64 / (segments + 1) as f64;
+            let mut point = p1.lerp(p2, t);
+            point.position = centripetal(p0.position, p1.position, p2.position, p3.position, t);
+            smoothed.push(point);
+        }
+    }
+    smoothed.extend(points.last());
+    smoothed
+}
+
+/// The point at `t` between `p1` and `p2` of a centripetal Catmull-Rom
+/// spline, evaluated with the Barry–Goldman pyramid.
+fn centripetal(
+    p0: LocalPoint,
+    p1: LocalPoint,
+    p2: LocalPoint,
+    p3: LocalPoint,
+    t: f64,
+) -> LocalPoint {
+    // Knot intervals, kept apart from zero for repeated points.
+    let interval = |a: LocalPoint, b: LocalPoint| (a - b).length().sqrt().max(1e-6);
+    let t0 = 0.0;
+    let t1 = t0 + interval(p0, p1);
+    let t2 = t1 + interval(p1, p2);
+    let t3 = t2 + interval(p2, p3);
+    let t = t1 + (t2 - t1) * t;
+
+    let mix = |a: LocalPoint, b: LocalPoint, ta: f64, tb: f64| a.lerp(b, (t - ta) / (tb - ta));
+    let a1 = mix(p0, p1, t0, t1);
+    let a2 = mix(p1, p2, t1, t2);
+    let a3 = mix(p2, p3, t2, t3);
+    let b1 = mix(a1, a2, t0, t2);
+    let b2 = mix(a2, a3, t1, t3);
+    mix(b1, b2, t1, t2)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        state::{
+            delivery::UpdateDelivery,
+            frame::LocalPoint,
+            gestures::{
+                drag::Drag,
+                stroke::{catmull_rom, simplify, Smoothing, Stroke, StrokePoint},
+            },
+            options::UseGesturesOptions,
+        },
+        testing::{CallbackLog, GestureHarness, PointerSequence, SyntheticPointer},
+        use_gestures::Gestures,
+    };
+
+    fn points(positions: &[(f64, f64)]) -> Vec<StrokePoint> {
+        positions
+            .iter()
+            .map(|&(x, y)| StrokePoint {
+                position: LocalPoint::new(x, y),
+                pressure: 0.5,
+                tangential_pressure: 0.0,
+                tilt_x: 0,
+                tilt_y: 0,
+                twist: 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn records_every_point_and_takes_precedence_over_drag() {
+        let log = CallbackLog::default();
+        let ended = Rc::new(RefCell::new(None));
+        let mut harness =
+            GestureHarness::new(Gestures::default().drag(log.drag(Drag::default())).stroke(
+                Stroke::default().on_end({
+                    let ended = Rc::clone(&ended);
+                    move |data| *ended.borrow_mut() = Some((data.svg_path(), data.points))
+                }),
+            ));
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::pen(1).pressure(0.25))
+                .move_to(SyntheticPointer::pen(1).at(1.0, 0.0).pressure(0.5))
+                .move_to(SyntheticPointer::pen(1).at(2.0, 1.0).pressure(0.75))
+                .up(1),
+        );
+
+        log.assert_eq(&[]);
+        let (path, points) = ended.borrow_mut().take().unwrap();
+        let pressures = points
+            .iter()
+            .map(|point| point.pressure)
+            .collect::<Vec<_>>();
+        assert_eq!(pressures, [0.25, 0.5, 0.75]);
+        assert_eq!(path, "M0 0 L1 0 L2 1");
+    }
+
+    #[test]
+    fn simplifies_and_smooths_on_end() {
+        let ended = Rc::new(RefCell::new(Vec::new()));
+        let mut harness = GestureHarness::new(
+            Gestures::default().stroke(
+                Stroke::default()
+                    .simplify(0.5)
+                    .smoothing(Smoothing::CatmullRom { segments: 1 })
+                    .on_end({
+                        let ended = Rc::clone(&ended);
+                        move |data| *ended.borrow_mut() = data.points
+                    }),
+            ),
+        );
+
+        harness.play(PointerSequence::drag(
+            SyntheticPointer::touch(1),
+            (10.0, 0.0),
+            10,
+        ));
+        let positions = ended
+            .borrow()
+            .iter()
+            .map(|point| point.position)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            [LocalPoint::new(0.0, 0.0), LocalPoint::new(10.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn simplification_keeps_corners() {
+        let simplified = simplify(
+            &points(&[
+                (0.0, 0.0),
+                (5.0, 0.1),
+                (10.0, 0.0),
+                (10.0, 5.0),
+                (10.1, 10.0),
+            ]),
+            1.0,
+        );
+        assert_eq!(simplified, points(&[(0.0, 0.0), (10.0, 0.0), (10.1, 10.0)]));
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_the_points() {
+        let original = points(&[(0.0, 0.0), (10.0, 10.0), (20.0, 0.0), (30.0, 10.0)]);
+        let smoothed = catmull_rom(&original, 3);
+
+        assert_eq!(smoothed.len(), 3 * 4 + 1);
+        for (index, point) in original.iter().enumerate() {
+            assert_eq!(smoothed[index * 4], *point);
+        }
+        // The curve overshoots the straight segment towards the next turn.
+        assert!(smoothed[1..4]
+            .iter()
+            .any(|point| point.position.y > point.position.x));
+    }
+
+    #[test]
+    fn per_frame_updates_keep_every_point() {
+        let updates = Rc::new(RefCell::new(Vec::new()));
+        let ended = Rc::new(RefCell::new(Vec::new()));
+        let mut harness = GestureHarness::new(
+            Gestures::default()
+                .options(UseGesturesOptions::default().update_delivery(UpdateDelivery::PerFrame))
+                .stroke(
+                    Stroke::default()
+                        .on_update({
+                            let updates = Rc::clone(&updates);
+                            move |data| updates.borrow_mut().push(data.points.len())
+                        })
+                        .on_end({
+                            let ended = Rc::clone(&ended);
+                            move |data| *ended.borrow_mut() = data.points
+                        }),
+                ),
+        );
+
+        harness
+            .play(
+                PointerSequence::default()
+                    .down(SyntheticPointer::pen(1))
+                    .move_by_steps(1, (3.0, 0.0), 3),
+            )
+            .frame()
+            .play(
+                PointerSequence::default()
+                    .move_to(SyntheticPointer::pen(1).at(3.0, 1.0))
+                    .move_to(SyntheticPointer::pen(1).at(3.0, 2.0))
+                    .up(1),
+            );
+
+        assert_eq!(*updates.borrow(), [3, 2]);
+        let positions = ended
+            .borrow()
+            .iter()
+            .map(|point| (point.position.x, point.position.y))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            [
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (2.0, 0.0),
+                (3.0, 0.0),
+                (3.0, 1.0),
+                (3.0, 2.0)
+            ]
+        );
+    }
+}
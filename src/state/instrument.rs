@@ -0,0 +1,62 @@
+//! Instrumentation of the gesture lifecycle with [`tracing`], enabled with the
+//! `tracing` feature. Without it, the macros and spans compile to nothing.
+//!
+//! Every pointer event a target receives opens a `pointer_event` span with the
+//! `target_id`, and every recognizer it is dispatched to a nested `recognizer`
+//! span, so threshold checks, transitions, capture calls and callbacks are
+//! attributed to both.
+//!
+//! [`tracing`]: https://docs.rs/tracing
+
+use dioxus::html::PointerData;
+
+use crate::state::events::PointerEventKind;
+
+#[cfg(feature = "tracing")]
+pub(crate) type EnteredSpan = tracing::span::EnteredSpan;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct EnteredSpan;
+
+pub(crate) fn enter_pointer_event(
+    target_id: &str,
+    kind: PointerEventKind,
+    data: &PointerData,
+) -> EnteredSpan {
+    #[cfg(feature = "tracing")]
+    return tracing::debug_span!(
+        "pointer_event",
+        target_id,
+        ?kind,
+        pointer_id = data.pointer_id(),
+        pointer_type = %data.pointer_type(),
+    )
+    .entered();
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = (target_id, kind, data.pointer_id());
+        EnteredSpan
+    }
+}
+
+pub(crate) fn enter_recognizer(name: &'static str) -> EnteredSpan {
+    #[cfg(feature = "tracing")]
+    return tracing::debug_span!("recognizer", name).entered();
+
+    #[cfg(not(feature = "tracing"))]
+    {
+        let _ = name;
+        EnteredSpan
+    }
+}
+
+/// Emits a `tracing` event with the given level, fields and message.
+macro_rules! event {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+    };
+}
+
+pub(crate) use event;
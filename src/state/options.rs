@@ -1,7 +1,21 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::state::{
+    capture::{CapturePolicy, PointerCaptureError},
+    delivery::UpdateDelivery,
+    frame::FrameMapping,
+    gestures::Handler,
+};
+
 #[derive(Clone)]
 pub struct UseGesturesOptions {
     pub target_id_attribute_name: &'static str,
     pub target_id: Option<String>,
+    pub capture_policy: CapturePolicy,
+    pub capture_secondary_buttons: bool,
+    pub on_pointer_capture_error: Option<Handler<PointerCaptureError>>,
+    pub frame_mapping: FrameMapping,
+    pub update_delivery: UpdateDelivery,
 }
 
 impl UseGesturesOptions {
@@ -18,8 +32,70 @@ impl UseGesturesOptions {
     }
 }
 
+impl UseGesturesOptions {
+    pub fn capture_policy(mut self, capture_policy: CapturePolicy) -> Self {
+        self.capture_policy = capture_policy;
+        self
+    }
+}
+
+impl UseGesturesOptions {
+    /// Whether pointers pressed without the primary button, such as a right or
+    /// middle click, are captured according to the capture policy. They are
+    /// left uncaptured by default so context menus and auxiliary clicks keep
+    /// reaching the element under the pointer.
+    pub fn capture_secondary_buttons(mut self, capture_secondary_buttons: bool) -> Self {
+        self.capture_secondary_buttons = capture_secondary_buttons;
+        self
+    }
+}
+
+impl UseGesturesOptions {
+    /// Called when capturing or releasing a pointer fails. Requires the `web`
+    /// feature, without it capture goes through `document::eval` and failures
+    /// go unnoticed.
+    pub fn on_pointer_capture_error(
+        mut self,
+        handler: impl FnMut(PointerCaptureError) + 'static,
+    ) -> Self {
+        self.on_pointer_capture_error = Some(Rc::new(RefCell::new(handler)));
+        self
+    }
+}
+
+impl UseGesturesOptions {
+    /// How the local coordinates of gesture data relate to the target. Requires
+    /// the `web` feature, without it local coordinates are relative to the
+    /// element the starting event was fired on.
+    ///
+    /// [`Drag::frame_mapping`] and [`Pinch::frame_mapping`] override it for a
+    /// single gesture.
+    ///
+    /// [`Drag::frame_mapping`]: crate::state::gestures::drag::Drag::frame_mapping
+    /// [`Pinch::frame_mapping`]: crate::state::gestures::pinch::Pinch::frame_mapping
+    pub fn frame_mapping(mut self, frame_mapping: FrameMapping) -> Self {
+        self.frame_mapping = frame_mapping;
+        self
+    }
+}
+
+impl UseGesturesOptions {
+    pub fn update_delivery(mut self, update_delivery: UpdateDelivery) -> Self {
+        self.update_delivery = update_delivery;
+        self
+    }
+}
+
 impl Default for UseGesturesOptions {
     fn default() -> Self {
-        Self { target_id_attribute_name: "data-gestures-id", target_id: None }
+        Self {
+            target_id_attribute_name: "data-gestures-id",
+            target_id: None,
+            capture_policy: CapturePolicy::default(),
+            capture_secondary_buttons: false,
+            on_pointer_capture_error: None,
+            frame_mapping: FrameMapping::default(),
+            update_delivery: UpdateDelivery::default(),
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,154 @@
+use std::{cell::RefCell, rc::Rc};
+
+use dioxus::{
+    html::{point_interaction::InteractionLocation, PointerData},
+    prelude::Event,
+};
+
+use crate::state::{
+    arbitration::Arbiter,
+    delivery::UpdateDelivery,
+    events::PointerEventReceiver,
+    frame::{FrameSource, LocalPoint, TargetFrame},
+    gestures::pointer::PointerId,
+};
+
+/// A recognizer of a target, with the name it is arbitrated by.
+pub type NamedRecognizer = (&'static str, Rc<RefCell<dyn GestureRecognizer>>);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecognizerPhase {
+    /// No gesture is tracked, either because no pointer is involved or because
+    /// the gesture failed or was cancelled.
+    #[default]
+    Idle,
+    /// Pointers are tracked, but the gesture has not started yet.
+    Possible,
+    Started,
+}
+
+/// A pointer tracked by a recognizer, with the data it was first tracked with,
+/// the data of its latest event and the frame of its target.
+#[derive(Clone)]
+pub struct TrackedPointer {
+    pub id: PointerId,
+    pub initial_data: Rc<PointerData>,
+    pub current_data: Rc<PointerData>,
+    pub frame: Rc<TargetFrame>,
+}
+
+impl TrackedPointer {
+    pub fn initial_local_coordinates(&self) -> LocalPoint {
+        self.frame
+            .local_point(self.initial_data.client_coordinates())
+    }
+
+    pub fn local_coordinates(&self) -> LocalPoint {
+        self.frame
+            .local_point(self.current_data.client_coordinates())
+    }
+}
+
+/// A gesture that receives every pointer event of its target.
+///
+/// [`Drag`], [`Pinch`] and [`Hover`] are recognizers themselves. Additional
+/// gestures are added with [`Gestures::recognizer`] and get the same events in
+/// the order they were registered, after the built-in ones.
+///
+/// Recognizers of the same target are arbitrated by name, see [`Arbitration`].
+/// A recognizer takes part by keeping the [`Arbiter`] it is attached to and
+/// asking [`Arbiter::try_start`] before it starts, and by reporting its
+/// [`RecognizerPhase`].
+///
+/// ```rust
+/// use dioxus::{html::PointerData, prelude::*};
+/// use dioxus_gestures::{
+///     state::{events::PointerEventReceiver, recognizer::GestureRecognizer},
+///     use_gestures::{use_gestures, Gestures},
+/// };
+///
+/// struct Clicks(Signal<usize>);
+///
+/// impl PointerEventReceiver<&Event<PointerData>> for Clicks {
+///     fn pointer_over(&mut self, _: &Event<PointerData>) {}
+///     fn pointer_enter(&mut self, _: &Event<PointerData>) {}
+///     fn pointer_down(&mut self, _: &Event<PointerData>) {}
+///     fn pointer_move(&mut self, _: &Event<PointerData>) {}
+///     fn pointer_up(&mut self, _: &Event<PointerData>) {
+///         *self.0.write() += 1;
+///     }
+///     fn pointer_cancel(&mut self, _: &Event<PointerData>) {}
+///     fn pointer_out(&mut self, _: &Event<PointerData>) {}
+///     fn pointer_leave(&mut self, _: &Event<PointerData>) {}
+/// }
+///
+/// impl GestureRecognizer for Clicks {}
+///
+/// #[component]
+/// pub fn ClickExample() -> Element {
+///     let clicks = use_signal(|| 0);
+///     let gestures = use_gestures(Gestures::default().recognizer("clicks", Clicks(clicks)));
+///
+///     rsx! {
+///         div {
+///             ..gestures.event_handlers(),
+///             "Clicked {clicks} times"
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Drag`]: crate::state::gestures::drag::Drag
+/// [`Pinch`]: crate::state::gestures::pinch::Pinch
+/// [`Hover`]: crate::state::gestures::hover::Hover
+/// [`Gestures::recognizer`]: crate::use_gestures::Gestures::recognizer
+/// [`Arbitration`]: crate::state::arbitration::Arbitration
+pub trait GestureRecognizer: for<'a> PointerEventReceiver<&'a Event<PointerData>> {
+    fn attach(&mut self, _arbiter: Arbiter) {}
+
+    /// Provides the [`FrameSource`] of the target, to measure its
+    /// [`TargetFrame`] when a gesture starts.
+    ///
+    /// [`TargetFrame`]: crate::state::frame::TargetFrame
+    fn attach_frames(&mut self, _frames: FrameSource) {}
+
+    fn set_update_delivery(&mut self, _delivery: UpdateDelivery) {}
+
+    /// Whether updates are held back until [`Self::flush_updates`].
+    fn has_pending_updates(&self) -> bool {
+        false
+    }
+
+    /// Delivers the held back updates, once per animation frame with
+    /// [`UpdateDelivery::PerFrame`].
+    fn flush_updates(&mut self) {}
+
+    /// Takes the timeouts requested since the preceding call, as pairs of a
+    /// delay in milliseconds and a token that is passed to [`Self::timeout`]
+    /// once the delay has passed, e.g. to recognize a long press.
+    fn take_timeouts(&mut self) -> Vec<(f64, u64)> {
+        Vec::new()
+    }
+
+    /// Called once the delay of a timeout from [`Self::take_timeouts`] has
+    /// passed.
+    fn timeout(&mut self, _token: u64) {}
+
+    fn phase(&self) -> RecognizerPhase {
+        RecognizerPhase::Idle
+    }
+
+    /// The pointers the recognizer currently tracks, for inspection.
+    fn tracked_pointers(&self) -> Vec<TrackedPointer> {
+        Vec::new()
+    }
+
+    /// Cancels a started gesture. A possible gesture is discarded until its
+    /// pointers are released.
+    fn cancel(&mut self) {}
+
+    /// Called after every event while the recognizer is
+    /// [`RecognizerPhase::Possible`], so a start that was denied by arbitration
+    /// can be retried.
+    fn resume(&mut self) {}
+}
@@ -0,0 +1,137 @@
+//! Deferred callbacks for update delivery and the timeouts of recognizers.
+//!
+//! With the `web` feature they go through `requestAnimationFrame` and
+//! `setTimeout` on the window, otherwise through `document::eval`. Outside of
+//! a dioxus runtime, frames are requested right away and timeouts run on the
+//! virtual clock of a [`GestureHarness`], or right away without the `testing`
+//! feature.
+//!
+//! [`GestureHarness`]: crate::testing::GestureHarness
+
+#[cfg(feature = "web")]
+use crate::state::instrument;
+
+/// Calls `callback` before the next repaint.
+#[cfg(feature = "web")]
+pub(crate) fn request_animation_frame(callback: impl FnOnce() + 'static) {
+    use web_sys::wasm_bindgen::{closure::Closure, JsCast};
+
+    let Some(window) = web_sys::window() else {
+        callback();
+        return;
+    };
+    let callback = Closure::once_into_js(callback);
+    if window
+        .request_animation_frame(callback.unchecked_ref())
+        .is_err()
+    {
+        instrument::event!(warn, "requestAnimationFrame failed");
+    }
+}
+
+/// Calls `callback` before the next repaint, once `requestAnimationFrame`
+/// resolves through `document::eval`.
+#[cfg(not(feature = "web"))]
+pub(crate) fn request_animation_frame(callback: impl FnOnce() + 'static) {
+    use dioxus::{core::Runtime, document, prelude::spawn};
+
+    if Runtime::try_current().is_none() {
+        callback();
+        return;
+    }
+    spawn(async move {
+        let _ = document::eval("await new Promise(requestAnimationFrame); return null;").await;
+        callback();
+    });
+}
+
+/// Calls `callback` after `delay` milliseconds.
+pub(crate) fn set_timeout(delay: f64, callback: impl FnOnce() + 'static) {
+    if dioxus::core::Runtime::try_current().is_none() {
+        #[cfg(any(test, feature = "testing"))]
+        virtual_clock::set_timeout(delay, callback);
+        #[cfg(not(any(test, feature = "testing")))]
+        callback();
+        return;
+    }
+
+    #[cfg(feature = "web")]
+    {
+        use web_sys::wasm_bindgen::{closure::Closure, JsCast};
+
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let callback = Closure::once_into_js(callback);
+        if window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(
+                callback.unchecked_ref(),
+                delay as i32,
+            )
+            .is_err()
+        {
+            instrument::event!(warn, "setTimeout failed");
+        }
+    }
+
+    #[cfg(not(feature = "web"))]
+    {
+        use dioxus::{document, prelude::spawn};
+
+        spawn(async move {
+            let _ = document::eval(&format!(
+                "await new Promise(resolve => setTimeout(resolve, {delay})); return null;"
+            ))
+            .await;
+            callback();
+        });
+    }
+}
+
+/// Timeouts of headless targets, which only run when the clock is advanced.
+#[cfg(any(test, feature = "testing"))]
+pub(crate) mod virtual_clock {
+    use std::cell::RefCell;
+
+    #[derive(Default)]
+    struct Clock {
+        now: f64,
+        timeouts: Vec<(f64, Box<dyn FnOnce()>)>,
+    }
+
+    thread_local! {
+        static CLOCK: RefCell<Clock> = RefCell::default();
+    }
+
+    pub(crate) fn set_timeout(delay: f64, callback: impl FnOnce() + 'static) {
+        CLOCK.with_borrow_mut(|clock| {
+            let due = clock.now + delay.max(0.0);
+            clock.timeouts.push((due, Box::new(callback)));
+        });
+    }
+
+    /// Advances the clock by `duration` milliseconds, running the timeouts
+    /// that become due in order, including those they set themselves.
+    pub(crate) fn advance(duration: f64) {
+        let end = CLOCK.with_borrow(|clock| clock.now) + duration;
+        loop {
+            let next = CLOCK.with_borrow_mut(|clock| {
+                let index = clock
+                    .timeouts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (due, _))| *due <= end)
+                    .min_by(|(_, (a, _)), (_, (b, _))| a.total_cmp(b))
+                    .map(|(index, _)| index)?;
+                let (due, callback) = clock.timeouts.remove(index);
+                clock.now = due;
+                Some(callback)
+            });
+            match next {
+                Some(callback) => callback(),
+                None => break,
+            }
+        }
+        CLOCK.with_borrow_mut(|clock| clock.now = end);
+    }
+}
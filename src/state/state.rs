@@ -1,15 +1,33 @@
-use dioxus::{document, html::PointerData, prelude::Event};
+use std::{cell::RefCell, rc::Rc};
+
+#[cfg(feature = "web")]
+use dioxus::html::MountedData;
+#[cfg(feature = "debug")]
+use dioxus::prelude::{Signal, WritableExt};
+#[cfg(not(feature = "web"))]
+use dioxus::{core::Runtime, document};
+use dioxus::{
+    html::{input_data::MouseButton, point_interaction::PointerInteraction, PointerData},
+    prelude::Event,
+};
+
+#[cfg(feature = "web")]
+use crate::state::capture::PointerCaptureError;
+#[cfg(any(test, feature = "trace"))]
+use crate::trace::GestureRecorder;
 
 use crate::state::{
-    events::PointerEventReceiver,
+    arbitration::{Arbiter, Arbitration, ArbitrationState},
+    arena::GestureArena,
+    capture::CaptureMode,
+    events::{PointerEventKind, PointerEventReceiver},
     external_handlers::ExternalHandlers,
-    gestures::{
-        down_pointer::DownPointerGestureState,
-        drag::Drag,
-        hover::{Hover, HoverGestureState},
-        pinch::Pinch,
-    },
+    frame::FrameSource,
+    gestures::pointer::PointerId,
+    instrument::{self, EnteredSpan},
     options::UseGesturesOptions,
+    recognizer::{GestureRecognizer, NamedRecognizer, RecognizerPhase},
+    schedule,
 };
 
 #[derive(Clone)]
@@ -17,41 +35,283 @@ pub struct UseGesturesState {
     pub target_id: String,
     pub options: UseGesturesOptions,
     external: ExternalHandlers,
-    hover: HoverGestureState,
-    down_pointer: DownPointerGestureState,
+    recognizers: Vec<NamedRecognizer>,
+    frames: FrameSource,
+    arbitration: Rc<ArbitrationState>,
+    captured_pointers: Vec<PointerId>,
+    frame_requested: bool,
+    #[cfg(feature = "web")]
+    element: Rc<RefCell<Option<web_sys::Element>>>,
+    #[cfg(any(test, feature = "trace"))]
+    recorder: Option<GestureRecorder>,
+    /// Incremented after every event, timeout and flush of held back updates,
+    /// so a [`GestureDebugOverlay`] can follow the state.
+    ///
+    /// [`GestureDebugOverlay`]: crate::debug::GestureDebugOverlay
+    #[cfg(feature = "debug")]
+    revision: Option<Signal<u64>>,
 }
 
 impl UseGesturesState {
     pub fn new(
         target_id: String,
         external: ExternalHandlers,
-        hover: Hover,
-        drag: Drag,
-        pinch: Pinch,
+        recognizers: Vec<NamedRecognizer>,
+        arbitration: Arbitration,
+        arena: Option<GestureArena>,
         options: UseGesturesOptions,
     ) -> Self {
+        let arbitration =
+            ArbitrationState::new(target_id.clone(), arbitration, arena, &recognizers);
+        #[cfg(feature = "web")]
+        let element = Rc::default();
+        #[cfg(feature = "web")]
+        let frames = FrameSource::new(&element, options.frame_mapping);
+        #[cfg(not(feature = "web"))]
+        let frames = FrameSource::default();
+        for (name, recognizer) in &recognizers {
+            let mut recognizer = recognizer.borrow_mut();
+            recognizer.attach(Arbiter::new(name, &arbitration));
+            recognizer.attach_frames(frames.clone());
+            recognizer.set_update_delivery(options.update_delivery);
+        }
+
         Self {
             target_id,
             external,
-            hover: HoverGestureState::new(hover),
-            down_pointer: DownPointerGestureState::new(drag, pinch),
+            recognizers,
+            frames,
+            arbitration,
+            captured_pointers: Vec::new(),
+            frame_requested: false,
+            #[cfg(feature = "web")]
+            element,
+            #[cfg(any(test, feature = "trace"))]
+            recorder: None,
+            #[cfg(feature = "debug")]
+            revision: None,
             options,
         }
     }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn captured_pointers(&self) -> &[PointerId] {
+        &self.captured_pointers
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn frames(&self) -> &FrameSource {
+        &self.frames
+    }
+
+    #[cfg(feature = "debug")]
+    pub(crate) fn recognizers(&self) -> &[NamedRecognizer] {
+        &self.recognizers
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub(crate) fn recognizer(&self, name: &str) -> Option<&Rc<RefCell<dyn GestureRecognizer>>> {
+        self.recognizers
+            .iter()
+            .find(|(recognizer, _)| *recognizer == name)
+            .map(|(_, recognizer)| recognizer)
+    }
+
+    #[cfg(any(test, feature = "trace"))]
+    pub(crate) fn set_recorder(&mut self, recorder: Option<GestureRecorder>) {
+        self.recorder = recorder;
+    }
+
+    #[cfg(feature = "debug")]
+    pub(crate) fn revision(&self) -> Option<Signal<u64>> {
+        self.revision
+    }
+
+    #[cfg(feature = "debug")]
+    pub(crate) fn set_revision(&mut self, revision: Signal<u64>) {
+        self.revision = Some(revision);
+    }
+
+    fn bump_revision(&self) {
+        #[cfg(feature = "debug")]
+        if let Some(mut revision) = self.revision {
+            *revision.write() += 1;
+        }
+    }
+
+    /// Records the event and enters its span, which lasts until the returned
+    /// guard is dropped.
+    fn receive(&self, kind: PointerEventKind, event: &Event<PointerData>) -> EnteredSpan {
+        let data = event.data();
+        #[cfg(any(test, feature = "trace"))]
+        if let Some(recorder) = &self.recorder {
+            recorder.record(kind, &data);
+        }
+        instrument::enter_pointer_event(&self.target_id, kind, &data)
+    }
+
+    fn dispatch(&self, mut f: impl FnMut(&mut dyn GestureRecognizer)) {
+        self.frames.next_event();
+        for (name, recognizer) in &self.recognizers {
+            let _span = instrument::enter_recognizer(name);
+            f(&mut *recognizer.borrow_mut());
+        }
+
+        for (name, recognizer) in &self.recognizers {
+            let phase = recognizer.borrow().phase();
+            if phase == RecognizerPhase::Possible {
+                let _span = instrument::enter_recognizer(name);
+                recognizer.borrow_mut().resume();
+            }
+        }
+        self.bump_revision();
+    }
+}
+
+impl UseGesturesState {
+    /// Whether an animation frame should be requested to flush held back
+    /// updates. Returns `true` at most once until [`Self::flush_updates`].
+    pub fn request_frame(&mut self) -> bool {
+        if self.frame_requested {
+            return false;
+        }
+        self.frame_requested = self
+            .recognizers
+            .iter()
+            .any(|(_, recognizer)| recognizer.borrow().has_pending_updates());
+        self.frame_requested
+    }
+
+    pub fn flush_updates(&mut self) {
+        self.frame_requested = false;
+        for (name, recognizer) in &self.recognizers {
+            let _span = instrument::enter_recognizer(name);
+            recognizer.borrow_mut().flush_updates();
+        }
+        self.bump_revision();
+    }
+
+    /// Sets the timeouts the recognizers requested, see
+    /// [`GestureRecognizer::take_timeouts`].
+    pub fn schedule_timeouts(state: &Rc<RefCell<Self>>) {
+        let Ok(this) = state.try_borrow() else {
+            return;
+        };
+        for (index, (_, recognizer)) in this.recognizers.iter().enumerate() {
+            for (delay, token) in recognizer.borrow_mut().take_timeouts() {
+                let state = Rc::downgrade(state);
+                schedule::set_timeout(delay, move || {
+                    if let Some(state) = state.upgrade() {
+                        Self::timeout(&state, index, token);
+                    }
+                });
+            }
+        }
+    }
+
+    fn timeout(state: &Rc<RefCell<Self>>, index: usize, token: u64) {
+        let Ok(this) = state.try_borrow_mut() else {
+            return;
+        };
+        let (name, recognizer) = &this.recognizers[index];
+        this.frames.next_event();
+        {
+            let _span = instrument::enter_recognizer(name);
+            recognizer.borrow_mut().timeout(token);
+        }
+        this.dispatch(|_| {});
+        drop(this);
+        Self::schedule_timeouts(state);
+    }
+}
+
+impl UseGesturesState {
+    /// Captures a pointer according to the [`CapturePolicy`]. Targets that take
+    /// part in a [`GestureArena`] capture at the earliest once one of their
+    /// recognizers claims the pointer and no inner target may still claim it,
+    /// and release the capture once an inner target takes the claim over.
+    ///
+    /// [`CapturePolicy`]: crate::state::capture::CapturePolicy
+    /// [`GestureArena`]: crate::state::arena::GestureArena
+    fn capture_pointer(&mut self, pointer_data: &PointerData, is_down: bool) {
+        let id = PointerId::from(pointer_data.pointer_id());
+        if self.arbitration.has_arena() {
+            self.arbitration.set_undecided(id, self.is_undecided(id));
+        }
+        if self.captured_pointers.contains(&id) {
+            if self.arbitration.has_arena() && !self.arbitration.is_claimed(id) {
+                self.release_capture(id);
+            }
+            return;
+        }
+        if !self.options.capture_secondary_buttons && is_secondary_press(pointer_data) {
+            return;
+        }
+
+        let capture = match self
+            .options
+            .capture_policy
+            .mode(&pointer_data.pointer_type())
+        {
+            CaptureMode::Never => false,
+            CaptureMode::Always if is_down && !self.arbitration.has_arena() => true,
+            CaptureMode::Always | CaptureMode::OnGestureStart => self.arbitration.may_capture(id),
+        };
+        if capture {
+            instrument::event!(
+                debug,
+                pointer_id = pointer_data.pointer_id(),
+                "set pointer capture"
+            );
+            self.captured_pointers.push(id);
+            self.set_pointer_capture(pointer_data.pointer_id());
+        }
+    }
+
+    fn release_pointer(&mut self, pointer_id: i32) {
+        let id = PointerId::from(pointer_id);
+        self.arbitration.leave(id);
+        self.release_capture(id);
+    }
+
+    fn release_capture(&mut self, id: PointerId) {
+        if let Some(index) = self.captured_pointers.iter().position(|p| *p == id) {
+            self.captured_pointers.remove(index);
+            instrument::event!(debug, pointer_id = id.0, "release pointer capture");
+            self.release_pointer_capture(id.0);
+        }
+    }
+
+    /// Whether a recognizer tracks the pointer and may still start with it.
+    fn is_undecided(&self, id: PointerId) -> bool {
+        self.recognizers.iter().any(|(_, recognizer)| {
+            let recognizer = recognizer.borrow();
+            recognizer.phase() == RecognizerPhase::Possible
+                && recognizer
+                    .tracked_pointers()
+                    .iter()
+                    .any(|pointer| pointer.id == id)
+        })
+    }
 }
 
+#[cfg(not(feature = "web"))]
 impl UseGesturesState {
     fn set_pointer_capture(&self, pointer_id: i32) {
+        if Runtime::try_current().is_none() {
+            return;
+        }
         let target_id_attribute_name = &self.options.target_id_attribute_name;
         let target_id = &self.target_id;
         document::eval(&format!(
             r#"document.querySelector("*[{target_id_attribute_name}='{target_id}']").setPointerCapture({pointer_id})"#,
         ));
     }
-}
 
-impl UseGesturesState {
     fn release_pointer_capture(&self, pointer_id: i32) {
+        if Runtime::try_current().is_none() {
+            return;
+        }
         let target_id_attribute_name = &self.options.target_id_attribute_name;
         let target_id = &self.target_id;
         document::eval(&format!(
@@ -60,58 +320,238 @@ impl UseGesturesState {
     }
 }
 
+#[cfg(feature = "web")]
+impl UseGesturesState {
+    /// Keeps the element handle of the target, if its renderer provides one.
+    pub fn mounted(&mut self, data: &MountedData) {
+        *self.element.borrow_mut() = data.downcast::<web_sys::Element>().cloned();
+    }
+
+    fn set_pointer_capture(&self, pointer_id: i32) {
+        let result = match &*self.element.borrow() {
+            Some(element) => element
+                .set_pointer_capture(pointer_id)
+                .map_err(|error| PointerCaptureError::rejected(pointer_id, error)),
+            None => Err(PointerCaptureError::NotMounted { pointer_id }),
+        };
+        self.report_capture_error(result);
+    }
+
+    fn release_pointer_capture(&self, pointer_id: i32) {
+        let result = match &*self.element.borrow() {
+            Some(element) => element
+                .release_pointer_capture(pointer_id)
+                .map_err(|error| PointerCaptureError::rejected(pointer_id, error)),
+            None => Err(PointerCaptureError::NotMounted { pointer_id }),
+        };
+        self.report_capture_error(result);
+    }
+
+    fn report_capture_error(&self, result: Result<(), PointerCaptureError>) {
+        let Err(error) = result else {
+            return;
+        };
+        instrument::event!(warn, %error, "pointer capture failed");
+        if let Some(handler) = &self.options.on_pointer_capture_error {
+            handler.borrow_mut()(error);
+        }
+    }
+}
+
 impl PointerEventReceiver<Event<PointerData>> for UseGesturesState {
     fn pointer_over(&mut self, event: Event<PointerData>) {
-        self.hover.pointer_over(&event);
-        self.down_pointer.pointer_over(&event);
+        let _span = self.receive(PointerEventKind::Over, &event);
+        self.dispatch(|recognizer| recognizer.pointer_over(&event));
         self.external.pointer_over(event);
     }
 
     fn pointer_enter(&mut self, event: Event<PointerData>) {
-        self.hover.pointer_enter(&event);
-        self.down_pointer.pointer_enter(&event);
+        let _span = self.receive(PointerEventKind::Enter, &event);
+        self.dispatch(|recognizer| recognizer.pointer_enter(&event));
         self.external.pointer_enter(event);
     }
 
     fn pointer_down(&mut self, event: Event<PointerData>) {
-        self.set_pointer_capture(event.pointer_id());
+        let _span = self.receive(PointerEventKind::Down, &event);
+        self.arbitration.enter(PointerId::from(event.pointer_id()));
 
-        self.hover.pointer_down(&event);
-        self.down_pointer.pointer_down(&event);
+        self.dispatch(|recognizer| recognizer.pointer_down(&event));
+        self.capture_pointer(&event.data(), true);
         self.external.pointer_down(event);
     }
 
     fn pointer_move(&mut self, event: Event<PointerData>) {
-        self.hover.pointer_move(&event);
-        self.down_pointer.pointer_move(&event);
+        let _span = self.receive(PointerEventKind::Move, &event);
+        self.dispatch(|recognizer| recognizer.pointer_move(&event));
+        self.capture_pointer(&event.data(), false);
         self.external.pointer_move(event);
     }
 
     fn pointer_up(&mut self, event: Event<PointerData>) {
-        self.release_pointer_capture(event.pointer_id());
+        let _span = self.receive(PointerEventKind::Up, &event);
+        self.release_pointer(event.pointer_id());
 
-        self.hover.pointer_up(&event);
-        self.down_pointer.pointer_up(&event);
+        self.dispatch(|recognizer| recognizer.pointer_up(&event));
         self.external.pointer_up(event);
     }
 
     fn pointer_cancel(&mut self, event: Event<PointerData>) {
-        self.release_pointer_capture(event.pointer_id());
+        let _span = self.receive(PointerEventKind::Cancel, &event);
+        self.release_pointer(event.pointer_id());
 
-        self.hover.pointer_cancel(&event);
-        self.down_pointer.pointer_cancel(&event);
+        self.dispatch(|recognizer| recognizer.pointer_cancel(&event));
         self.external.pointer_cancel(event);
     }
 
     fn pointer_out(&mut self, event: Event<PointerData>) {
-        self.hover.pointer_out(&event);
-        self.down_pointer.pointer_out(&event);
+        let _span = self.receive(PointerEventKind::Out, &event);
+        self.dispatch(|recognizer| recognizer.pointer_out(&event));
         self.external.pointer_out(event);
     }
 
     fn pointer_leave(&mut self, event: Event<PointerData>) {
-        self.hover.pointer_leave(&event);
-        self.down_pointer.pointer_leave(&event);
+        let _span = self.receive(PointerEventKind::Leave, &event);
+        self.dispatch(|recognizer| recognizer.pointer_leave(&event));
         self.external.pointer_leave(event);
     }
+
+    fn got_pointer_capture(&mut self, event: Event<PointerData>) {
+        let _span = self.receive(PointerEventKind::GotCapture, &event);
+        self.dispatch(|recognizer| recognizer.got_pointer_capture(&event));
+        self.external.got_pointer_capture(event);
+    }
+
+    /// A capture that is lost while the pointer is still down was revoked by
+    /// the browser, e.g. because the target was removed or another element
+    /// captured the pointer. The gestures of that pointer are cancelled.
+    fn lost_pointer_capture(&mut self, event: Event<PointerData>) {
+        let _span = self.receive(PointerEventKind::LostCapture, &event);
+        let id = PointerId::from(event.pointer_id());
+        if let Some(index) = self.captured_pointers.iter().position(|p| *p == id) {
+            self.captured_pointers.remove(index);
+            instrument::event!(
+                debug,
+                pointer_id = event.pointer_id(),
+                "pointer capture lost"
+            );
+            self.arbitration.leave(id);
+            self.dispatch(|recognizer| recognizer.lost_pointer_capture(&event));
+        }
+        self.external.lost_pointer_capture(event);
+    }
+}
+
+/// Whether the pointer is pressed with buttons other than the primary one only.
+fn is_secondary_press(pointer_data: &PointerData) -> bool {
+    let held_buttons = pointer_data.held_buttons();
+    !held_buttons.contains(MouseButton::Primary)
+        && (!held_buttons.is_empty()
+            || pointer_data
+                .trigger_button()
+                .is_some_and(|button| button != MouseButton::Primary))
+}
+
+#[cfg(test)]
+mod tests {
+    use dioxus::html::input_data::MouseButton;
+
+    use crate::{
+        state::{
+            capture::{CaptureMode, CapturePolicy},
+            gestures::drag::Drag,
+            options::UseGesturesOptions,
+        },
+        testing::{
+            CallbackLog, GestureHarness, PointerEventKind, PointerSequence, SyntheticPointer,
+        },
+        use_gestures::Gestures,
+    };
+
+    fn harness(capture_policy: CapturePolicy) -> GestureHarness {
+        GestureHarness::new(
+            Gestures::default()
+                .options(UseGesturesOptions::default().capture_policy(capture_policy)),
+        )
+    }
+
+    #[test]
+    fn captures_according_to_the_policy() {
+        let down = PointerSequence::default().down(SyntheticPointer::touch(1));
+        let drag = PointerSequence::default().move_to(SyntheticPointer::touch(1).at(10.0, 0.0));
+
+        let mut always = harness(CapturePolicy::Always);
+        always.play(down.clone());
+        assert_eq!(always.captured_pointers(), [1]);
+
+        let mut on_gesture_start = harness(CapturePolicy::OnGestureStart);
+        on_gesture_start.play(down.clone());
+        assert!(on_gesture_start.captured_pointers().is_empty());
+        on_gesture_start.play(drag.clone());
+        assert_eq!(on_gesture_start.captured_pointers(), [1]);
+
+        let mut never = harness(CapturePolicy::Never);
+        never.play(down.clone().then(drag));
+        assert!(never.captured_pointers().is_empty());
+
+        let mut per_pointer_type = harness(CapturePolicy::PerPointerType {
+            mouse: CaptureMode::Never,
+            pen: CaptureMode::Never,
+            touch: CaptureMode::Always,
+        });
+        per_pointer_type.play(down.down(SyntheticPointer::pen(2)));
+        assert_eq!(per_pointer_type.captured_pointers(), [1]);
+    }
+
+    #[test]
+    fn leaves_secondary_button_presses_uncaptured() {
+        let right_click = SyntheticPointer::mouse()
+            .buttons(Some(MouseButton::Secondary), MouseButton::Secondary.into());
+        let down = PointerSequence::default().down(right_click.clone());
+
+        let mut harness = harness(CapturePolicy::Always);
+        harness.play(down.clone().move_to(right_click.clone().at(10.0, 0.0)));
+        assert!(harness.captured_pointers().is_empty());
+
+        let mut capture_secondary_buttons = GestureHarness::new(
+            Gestures::default().options(
+                UseGesturesOptions::default()
+                    .capture_policy(CapturePolicy::Always)
+                    .capture_secondary_buttons(true),
+            ),
+        );
+        capture_secondary_buttons.play(down);
+        assert_eq!(capture_secondary_buttons.captured_pointers(), [1]);
+    }
+
+    #[test]
+    fn releases_on_pointer_up() {
+        let mut harness = harness(CapturePolicy::Always);
+        harness.play(PointerSequence::drag(
+            SyntheticPointer::touch(1),
+            (10.0, 0.0),
+            2,
+        ));
+        assert!(harness.captured_pointers().is_empty());
+    }
+
+    #[test]
+    fn lost_capture_cancels_the_gestures_of_the_pointer() {
+        let log = CallbackLog::default();
+        let mut harness = GestureHarness::new(Gestures::default().drag(log.drag(Drag::default())));
+
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::touch(1))
+                .move_to(SyntheticPointer::touch(1).at(10.0, 0.0))
+                .event(
+                    PointerEventKind::LostCapture,
+                    SyntheticPointer::touch(1).at(10.0, 0.0),
+                )
+                .move_to(SyntheticPointer::touch(1).at(20.0, 0.0))
+                .up(1),
+        );
+
+        log.assert_eq(&["drag.start", "drag.cancel"]);
+        assert!(harness.captured_pointers().is_empty());
+    }
 }
@@ -0,0 +1,661 @@
+//! Headless testing of gesture targets, enabled with the `testing` feature.
+//!
+//! A [`GestureHarness`] feeds synthetic pointer events through the same state a
+//! mounted target uses, without a browser or a dioxus runtime. Pointer capture
+//! is skipped, but which pointers would be captured can be inspected.
+//!
+//! ```rust
+//! use dioxus_gestures::{
+//!     state::gestures::{drag::Drag, pinch::Pinch},
+//!     testing::{CallbackLog, GestureHarness, PointerSequence, SyntheticPointer},
+//!     use_gestures::Gestures,
+//! };
+//!
+//! let log = CallbackLog::default();
+//! let mut harness = GestureHarness::new(
+//!     Gestures::default()
+//!         .drag(log.drag(Drag::default()))
+//!         .pinch(log.pinch(Pinch::default())),
+//! );
+//!
+//! harness.play(PointerSequence::drag(SyntheticPointer::touch(1), (40.0, 0.0), 4));
+//! log.assert_order(&["drag.start", "drag.update", "drag.end"]);
+//!
+//! harness.play(PointerSequence::pinch((100.0, 100.0), 50.0, 150.0, 4));
+//! log.assert_order(&["pinch.start", "pinch.update", "pinch.end"]);
+//! ```
+
+use nanoid::nanoid;
+use std::{cell::RefCell, rc::Rc};
+
+use dioxus::{
+    html::{
+        geometry::{euclid::Rect, ClientPoint, ClientSpace, ElementPoint, PagePoint, ScreenPoint},
+        input_data::{MouseButton, MouseButtonSet},
+        point_interaction::{
+            InteractionElementOffset, InteractionLocation, ModifiersInteraction, PointerInteraction,
+        },
+        HasPointerData, PointerData,
+    },
+    prelude::{Event, Modifiers},
+};
+
+use crate::{
+    state::{
+        gestures::{drag::Drag, hover::Hover, pinch::Pinch, Handler},
+        recognizer::RecognizerPhase,
+        schedule::virtual_clock,
+        state::UseGesturesState,
+    },
+    use_gestures::{Gestures, UseGestures},
+};
+
+#[cfg(any(test, feature = "trace"))]
+use crate::trace::{PointerTrace, TracedEvent};
+#[cfg(any(test, feature = "trace"))]
+use dioxus::html::input_data::decode_mouse_button_set;
+
+pub use crate::state::events::PointerEventKind;
+
+/// The values of a synthetic pointer event.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntheticPointer {
+    pub pointer_id: i32,
+    pub pointer_type: String,
+    pub is_primary: bool,
+    pub client: ClientPoint,
+    pub screen: ScreenPoint,
+    pub page: PagePoint,
+    pub element: ElementPoint,
+    pub width: f64,
+    pub height: f64,
+    pub pressure: f32,
+    pub tangential_pressure: f32,
+    pub tilt_x: i32,
+    pub tilt_y: i32,
+    pub twist: i32,
+    pub trigger_button: Option<MouseButton>,
+    pub held_buttons: MouseButtonSet,
+    pub modifiers: Modifiers,
+}
+
+impl SyntheticPointer {
+    pub fn new(pointer_id: i32, pointer_type: &str) -> Self {
+        Self {
+            pointer_id,
+            pointer_type: pointer_type.to_string(),
+            is_primary: true,
+            client: ClientPoint::zero(),
+            screen: ScreenPoint::zero(),
+            page: PagePoint::zero(),
+            element: ElementPoint::zero(),
+            width: 1.0,
+            height: 1.0,
+            pressure: 0.0,
+            tangential_pressure: 0.0,
+            tilt_x: 0,
+            tilt_y: 0,
+            twist: 0,
+            trigger_button: None,
+            held_buttons: MouseButtonSet::empty(),
+            modifiers: Modifiers::empty(),
+        }
+    }
+
+    pub fn mouse() -> Self {
+        Self::new(1, "mouse")
+    }
+
+    pub fn touch(pointer_id: i32) -> Self {
+        Self::new(pointer_id, "touch")
+    }
+
+    pub fn pen(pointer_id: i32) -> Self {
+        Self::new(pointer_id, "pen")
+    }
+
+    /// Moves the pointer to `(x, y)` in all coordinate spaces.
+    pub fn at(mut self, x: f64, y: f64) -> Self {
+        self.client = ClientPoint::new(x, y);
+        self.screen = ScreenPoint::new(x, y);
+        self.page = PagePoint::new(x, y);
+        self.element = ElementPoint::new(x, y);
+        self
+    }
+
+    /// Sets the element coordinates alone, as for an event fired by a target
+    /// that is not at the origin of the client area, or by a child element.
+    pub fn element_at(mut self, x: f64, y: f64) -> Self {
+        self.element = ElementPoint::new(x, y);
+        self
+    }
+
+    pub fn primary(mut self, is_primary: bool) -> Self {
+        self.is_primary = is_primary;
+        self
+    }
+
+    pub fn pressure(mut self, pressure: f32) -> Self {
+        self.pressure = pressure;
+        self
+    }
+
+    pub fn tilt(mut self, tilt_x: i32, tilt_y: i32) -> Self {
+        self.tilt_x = tilt_x;
+        self.tilt_y = tilt_y;
+        self
+    }
+
+    pub fn twist(mut self, twist: i32) -> Self {
+        self.twist = twist;
+        self
+    }
+
+    pub fn buttons(
+        mut self,
+        trigger_button: Option<MouseButton>,
+        held_buttons: MouseButtonSet,
+    ) -> Self {
+        self.trigger_button = trigger_button;
+        self.held_buttons = held_buttons;
+        self
+    }
+
+    pub fn modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    pub fn to_event(&self) -> Event<PointerData> {
+        Event::new(Rc::new(PointerData::new(self.clone())), true)
+    }
+}
+
+#[cfg(any(test, feature = "trace"))]
+impl From<&TracedEvent> for SyntheticPointer {
+    fn from(event: &TracedEvent) -> Self {
+        Self {
+            pointer_id: event.pointer_id,
+            pointer_type: event.pointer_type.clone(),
+            is_primary: event.is_primary,
+            client: event.client.into(),
+            screen: event.screen.into(),
+            page: event.page.into(),
+            element: event.element.into(),
+            width: event.width,
+            height: event.height,
+            pressure: event.pressure,
+            tangential_pressure: event.tangential_pressure,
+            tilt_x: event.tilt_x,
+            tilt_y: event.tilt_y,
+            twist: event.twist,
+            trigger_button: event.button.map(MouseButton::from_web_code),
+            held_buttons: decode_mouse_button_set(event.buttons),
+            modifiers: Modifiers::from_bits_truncate(event.modifiers),
+        }
+    }
+}
+
+/// The events of a trace, without their timing, which
+/// [`GestureHarness::replay`] keeps.
+#[cfg(any(test, feature = "trace"))]
+impl From<&PointerTrace> for PointerSequence {
+    fn from(trace: &PointerTrace) -> Self {
+        Self {
+            events: trace
+                .events
+                .iter()
+                .map(|event| (event.kind, SyntheticPointer::from(event)))
+                .collect(),
+        }
+    }
+}
+
+impl InteractionLocation for SyntheticPointer {
+    fn client_coordinates(&self) -> ClientPoint {
+        self.client
+    }
+
+    fn screen_coordinates(&self) -> ScreenPoint {
+        self.screen
+    }
+
+    fn page_coordinates(&self) -> PagePoint {
+        self.page
+    }
+}
+
+impl InteractionElementOffset for SyntheticPointer {
+    fn element_coordinates(&self) -> ElementPoint {
+        self.element
+    }
+}
+
+impl ModifiersInteraction for SyntheticPointer {
+    fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+}
+
+impl PointerInteraction for SyntheticPointer {
+    fn trigger_button(&self) -> Option<MouseButton> {
+        self.trigger_button
+    }
+
+    fn held_buttons(&self) -> MouseButtonSet {
+        self.held_buttons
+    }
+}
+
+impl HasPointerData for SyntheticPointer {
+    fn pointer_id(&self) -> i32 {
+        self.pointer_id
+    }
+
+    fn width(&self) -> f64 {
+        self.width
+    }
+
+    fn height(&self) -> f64 {
+        self.height
+    }
+
+    fn pressure(&self) -> f32 {
+        self.pressure
+    }
+
+    fn tangential_pressure(&self) -> f32 {
+        self.tangential_pressure
+    }
+
+    fn tilt_x(&self) -> i32 {
+        self.tilt_x
+    }
+
+    fn tilt_y(&self) -> i32 {
+        self.tilt_y
+    }
+
+    fn twist(&self) -> i32 {
+        self.twist
+    }
+
+    fn pointer_type(&self) -> String {
+        self.pointer_type.clone()
+    }
+
+    fn is_primary(&self) -> bool {
+        self.is_primary
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Pointer events in the order they are sent to a target.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PointerSequence {
+    pub events: Vec<(PointerEventKind, SyntheticPointer)>,
+}
+
+impl PointerSequence {
+    pub fn event(mut self, kind: PointerEventKind, pointer: SyntheticPointer) -> Self {
+        self.events.push((kind, pointer));
+        self
+    }
+
+    /// Sends `pointerover`, `pointerenter` and `pointerdown`.
+    pub fn down(self, pointer: SyntheticPointer) -> Self {
+        self.event(PointerEventKind::Over, pointer.clone())
+            .event(PointerEventKind::Enter, pointer.clone())
+            .event(PointerEventKind::Down, pointer)
+    }
+
+    pub fn move_to(self, pointer: SyntheticPointer) -> Self {
+        self.event(PointerEventKind::Move, pointer)
+    }
+
+    /// Moves the pointer from its last position in the sequence to `(x, y)` in
+    /// `steps` equal steps.
+    pub fn move_by_steps(mut self, pointer_id: i32, (x, y): (f64, f64), steps: usize) -> Self {
+        let Some(pointer) = self.last(pointer_id) else {
+            return self;
+        };
+        let from = pointer.client;
+        for step in 1..=steps {
+            let t = step as f64 / steps as f64;
+            let position = from.lerp(ClientPoint::new(x, y), t);
+            self.events.push((
+                PointerEventKind::Move,
+                pointer.clone().at(position.x, position.y),
+            ));
+        }
+        self
+    }
+
+    /// Sends `pointerup`, `pointerout` and `pointerleave` at the last position
+    /// of the pointer in the sequence. Pointers that are not part of the
+    /// sequence are ignored.
+    pub fn up(self, pointer_id: i32) -> Self {
+        self.end(pointer_id, PointerEventKind::Up)
+    }
+
+    /// Sends `pointercancel`, `pointerout` and `pointerleave` at the last
+    /// position of the pointer in the sequence.
+    pub fn cancel(self, pointer_id: i32) -> Self {
+        self.end(pointer_id, PointerEventKind::Cancel)
+    }
+
+    pub fn then(mut self, other: PointerSequence) -> Self {
+        self.events.extend(other.events);
+        self
+    }
+
+    /// A pointer that goes down, moves to `to` in `steps` steps and goes up.
+    pub fn drag(pointer: SyntheticPointer, to: (f64, f64), steps: usize) -> Self {
+        let pointer_id = pointer.pointer_id;
+        Self::default()
+            .down(pointer)
+            .move_by_steps(pointer_id, to, steps)
+            .up(pointer_id)
+    }
+
+    /// Two touch pointers with ids `1` and `2`, placed horizontally around
+    /// `center`, that move from `from_distance` to `to_distance` apart in
+    /// `steps` steps, moving alternately.
+    pub fn pinch(center: (f64, f64), from_distance: f64, to_distance: f64, steps: usize) -> Self {
+        let (x, y) = center;
+        let mut sequence = Self::default()
+            .down(SyntheticPointer::touch(1).at(x - from_distance / 2.0, y))
+            .down(
+                SyntheticPointer::touch(2)
+                    .primary(false)
+                    .at(x + from_distance / 2.0, y),
+            );
+        for step in 1..=steps {
+            let t = step as f64 / steps as f64;
+            let distance = from_distance + (to_distance - from_distance) * t;
+            sequence = sequence
+                .move_by_steps(1, (x - distance / 2.0, y), 1)
+                .move_by_steps(2, (x + distance / 2.0, y), 1);
+        }
+        sequence.up(1).up(2)
+    }
+
+    fn last(&self, pointer_id: i32) -> Option<SyntheticPointer> {
+        self.events
+            .iter()
+            .rev()
+            .find(|(_, pointer)| pointer.pointer_id == pointer_id)
+            .map(|(_, pointer)| pointer.clone())
+    }
+
+    fn end(self, pointer_id: i32, kind: PointerEventKind) -> Self {
+        let Some(pointer) = self.last(pointer_id) else {
+            return self;
+        };
+        self.event(kind, pointer.clone())
+            .event(PointerEventKind::Out, pointer.clone())
+            .event(PointerEventKind::Leave, pointer)
+    }
+}
+
+/// A gesture target without an element.
+pub struct GestureHarness {
+    gestures: UseGestures,
+}
+
+impl GestureHarness {
+    /// Creates the target with the id from the options, or a random one.
+    pub fn new(gestures: Gestures) -> Self {
+        let target_id = gestures
+            .options
+            .target_id
+            .clone()
+            .unwrap_or_else(|| nanoid!());
+        Self {
+            gestures: UseGestures::new(target_id, gestures),
+        }
+    }
+
+    pub fn send(&mut self, kind: PointerEventKind, pointer: &SyntheticPointer) -> &mut Self {
+        kind.dispatch(&mut *self.gestures.state.borrow_mut(), pointer.to_event());
+        UseGesturesState::schedule_timeouts(&self.gestures.state);
+        self
+    }
+
+    pub fn play(&mut self, sequence: PointerSequence) -> &mut Self {
+        for (kind, pointer) in &sequence.events {
+            self.send(*kind, pointer);
+        }
+        self
+    }
+
+    /// Measures the target with the given client rect, as the mounted target is
+    /// measured with the `web` feature, instead of deriving its frame from the
+    /// element coordinates of the events.
+    pub fn target_rect(&mut self, rect: Rect<f64, ClientSpace>) -> &mut Self {
+        self.gestures.state.borrow().frames().set_rect(rect);
+        self
+    }
+
+    /// Advances the clock of deferred callbacks, like the timeouts of a
+    /// [`HoverIntent`] or a long press, by `milliseconds`.
+    ///
+    /// [`HoverIntent`]: crate::state::gestures::hover::HoverIntent
+    pub fn advance(&mut self, milliseconds: f64) -> &mut Self {
+        virtual_clock::advance(milliseconds);
+        self
+    }
+
+    /// Delivers the updates held back until the next animation frame, see
+    /// [`UpdateDelivery::PerFrame`].
+    ///
+    /// [`UpdateDelivery::PerFrame`]: crate::state::delivery::UpdateDelivery::PerFrame
+    pub fn frame(&mut self) -> &mut Self {
+        self.gestures.state.borrow_mut().flush_updates();
+        self
+    }
+
+    /// Sends the events of a recorded trace, advancing the clock by the time
+    /// between them, so timeouts like those of a long press run as they did
+    /// while recording.
+    #[cfg(any(test, feature = "trace"))]
+    pub fn replay(&mut self, trace: &PointerTrace) -> &mut Self {
+        let mut time = trace.events.first().map_or(0.0, |event| event.time);
+        for event in &trace.events {
+            self.advance((event.time - time).max(0.0));
+            time = time.max(event.time);
+            self.send(event.kind, &SyntheticPointer::from(event));
+        }
+        self
+    }
+
+    /// The phase of the recognizer registered as `name`.
+    pub fn phase(&self, name: &str) -> Option<RecognizerPhase> {
+        let state = self.gestures.state.borrow();
+        let phase = state.recognizer(name)?.borrow().phase();
+        Some(phase)
+    }
+
+    /// The pointers tracked by the recognizer registered as `name`.
+    pub fn tracked_pointers(&self, name: &str) -> Vec<i32> {
+        let state = self.gestures.state.borrow();
+        state.recognizer(name).map_or_else(Vec::new, |recognizer| {
+            recognizer
+                .borrow()
+                .tracked_pointers()
+                .iter()
+                .map(|pointer| pointer.id.0)
+                .collect()
+        })
+    }
+
+    /// The pointers the target holds captured.
+    pub fn captured_pointers(&self) -> Vec<i32> {
+        self.gestures
+            .state
+            .borrow()
+            .captured_pointers()
+            .iter()
+            .map(|pointer| pointer.0)
+            .collect()
+    }
+}
+
+/// Records the names of the callbacks that fired, in order.
+///
+/// The gesture methods wrap the callbacks of a gesture, the handlers that are
+/// already set are still called. Callbacks whose presence changes what the
+/// recognizer does, like the hover intent callbacks, are only recorded if they
+/// are set, so that the log does not change the behavior under test. Drag callbacks are recorded as
+/// `"drag.start"`, `"drag.update"`, `"drag.end"` and `"drag.cancel"`, pinch
+/// callbacks as `"pinch.*"` and hover callbacks as `"hover.start"`,
+/// `"hover.end"`, `"hover.cancel"`, `"hover.pointer_appear"`,
+/// `"hover.pointer_update"`, `"hover.pointer_disappear"`,
+/// `"hover.pointer_cancel"`, `"hover.intent_start"`, `"hover.intent_end"`,
+/// `"hover.pen_hover"`, `"hover.pen_proximity_enter"` and
+/// `"hover.pen_proximity_leave"`.
+#[derive(Clone, Default)]
+pub struct CallbackLog {
+    entries: Rc<RefCell<Vec<&'static str>>>,
+}
+
+impl CallbackLog {
+    pub fn record<T>(&self, name: &'static str) -> impl FnMut(T) + 'static {
+        let entries = Rc::clone(&self.entries);
+        move |_| entries.borrow_mut().push(name)
+    }
+
+    pub fn drag(&self, drag: Drag) -> Drag {
+        Drag {
+            on_start: self.wrap_all("drag.start", drag.on_start),
+            on_update: self.wrap_all("drag.update", drag.on_update),
+            on_end: self.wrap_all("drag.end", drag.on_end),
+            on_cancel: self.wrap_all("drag.cancel", drag.on_cancel),
+            has_started: drag.has_started,
+            frame_mapping: drag.frame_mapping,
+        }
+    }
+
+    pub fn pinch(&self, pinch: Pinch) -> Pinch {
+        Pinch {
+            on_start: self.wrap_all("pinch.start", pinch.on_start),
+            on_update: self.wrap_all("pinch.update", pinch.on_update),
+            on_end: self.wrap_all("pinch.end", pinch.on_end),
+            on_cancel: self.wrap_all("pinch.cancel", pinch.on_cancel),
+            has_started: pinch.has_started,
+            joint_updates: pinch.joint_updates,
+            frame_mapping: pinch.frame_mapping,
+        }
+    }
+
+    pub fn hover(&self, hover: Hover) -> Hover {
+        Hover {
+            on_start: self.wrap_all("hover.start", hover.on_start),
+            on_end: self.wrap_all("hover.end", hover.on_end),
+            on_cancel: self.wrap_all("hover.cancel", hover.on_cancel),
+            on_intent_start: self.wrap("hover.intent_start", hover.on_intent_start),
+            on_intent_end: self.wrap("hover.intent_end", hover.on_intent_end),
+            intent: hover.intent,
+            touch: hover.touch,
+            on_pointer_appear: self.wrap_all("hover.pointer_appear", hover.on_pointer_appear),
+            on_pointer_update: self.wrap_all("hover.pointer_update", hover.on_pointer_update),
+            on_pointer_disappear: self
+                .wrap_all("hover.pointer_disappear", hover.on_pointer_disappear),
+            on_pointer_cancel: self.wrap_all("hover.pointer_cancel", hover.on_pointer_cancel),
+            on_pen_hover: self.wrap_all("hover.pen_hover", hover.on_pen_hover),
+            on_pen_proximity_enter: self
+                .wrap_all("hover.pen_proximity_enter", hover.on_pen_proximity_enter),
+            on_pen_proximity_leave: self
+                .wrap_all("hover.pen_proximity_leave", hover.on_pen_proximity_leave),
+        }
+    }
+
+    pub fn entries(&self) -> Vec<&'static str> {
+        self.entries.borrow().clone()
+    }
+
+    /// Returns the entries and clears the log.
+    pub fn take(&self) -> Vec<&'static str> {
+        std::mem::take(&mut *self.entries.borrow_mut())
+    }
+
+    pub fn count(&self, name: &str) -> usize {
+        self.entries
+            .borrow()
+            .iter()
+            .filter(|entry| **entry == name)
+            .count()
+    }
+
+    /// Asserts the exact entries and clears the log.
+    #[track_caller]
+    pub fn assert_eq(&self, expected: &[&str]) {
+        assert_eq!(self.take(), expected, "unexpected gesture callbacks");
+    }
+
+    /// Asserts the entries with repetitions collapsed, so that any number of
+    /// consecutive `"drag.update"` entries match a single one, and clears the
+    /// log.
+    #[track_caller]
+    pub fn assert_order(&self, expected: &[&str]) {
+        let mut entries = self.take();
+        entries.dedup();
+        assert_eq!(entries, expected, "unexpected order of gesture callbacks");
+    }
+
+    /// Records the calls of `handler`, if it is set.
+    fn wrap<T: 'static>(
+        &self,
+        name: &'static str,
+        handler: Option<Handler<T>>,
+    ) -> Option<Handler<T>> {
+        let handler = handler?;
+        let entries = Rc::clone(&self.entries);
+        Some(Rc::new(RefCell::new(move |data: T| {
+            entries.borrow_mut().push(name);
+            handler.borrow_mut()(data);
+        })))
+    }
+
+    /// Records the calls of a callback whether a handler is set or not.
+    fn wrap_all<T: 'static>(
+        &self,
+        name: &'static str,
+        handler: Option<Handler<T>>,
+    ) -> Option<Handler<T>> {
+        let handler = handler.unwrap_or_else(|| Rc::new(RefCell::new(|_| {})));
+        self.wrap(name, Some(handler))
+    }
+}
+
+/// Arbitrary pointer events of touch pointers `1` to `3`, which don't need to
+/// be in an order a browser would send them in, followed by `pointerup` and
+/// `pointerleave` for every pointer.
+#[cfg(test)]
+pub(crate) fn arbitrary_sequence() -> impl proptest::strategy::Strategy<Value = PointerSequence> {
+    use proptest::prelude::*;
+
+    let kind = prop_oneof![
+        1 => Just(PointerEventKind::Enter),
+        1 => Just(PointerEventKind::Down),
+        4 => Just(PointerEventKind::Move),
+        1 => Just(PointerEventKind::Up),
+        1 => Just(PointerEventKind::Cancel),
+        1 => Just(PointerEventKind::Out),
+        1 => Just(PointerEventKind::Leave),
+        1 => Just(PointerEventKind::LostCapture),
+    ];
+    let event = (kind, 1..=3, 0.0..100.0, 0.0..100.0)
+        .prop_map(|(kind, pointer_id, x, y)| (kind, SyntheticPointer::touch(pointer_id).at(x, y)));
+
+    proptest::collection::vec(event, 0..60).prop_map(|events| {
+        (1..=3).fold(PointerSequence { events }, |sequence, pointer_id| {
+            sequence
+                .event(PointerEventKind::Up, SyntheticPointer::touch(pointer_id))
+                .event(PointerEventKind::Leave, SyntheticPointer::touch(pointer_id))
+        })
+    })
+}
@@ -0,0 +1,306 @@
+//! Recording of the raw pointer events of a gesture target, enabled with the
+//! `trace` feature.
+//!
+//! A [`GestureRecorder`] added with [`Gestures::recorder`] appends every pointer
+//! event the target receives to a [`PointerTrace`], which serializes to JSON.
+//! With the `testing` feature, a trace is replayed with
+//! [`GestureHarness::replay`] and reproduces the callbacks of the recorded
+//! session, so traces from the field can be kept as regression fixtures.
+//!
+//! ```rust
+//! use dioxus::prelude::*;
+//! use dioxus_gestures::{
+//!     trace::GestureRecorder,
+//!     use_gestures::{use_gestures, Gestures},
+//! };
+//!
+//! #[component]
+//! pub fn RecordedTarget() -> Element {
+//!     let recorder = use_hook(GestureRecorder::new);
+//!     let gestures = use_gestures(Gestures::default().recorder(recorder.clone()));
+//!
+//!     rsx! {
+//!         div {
+//!             ..gestures.event_handlers(),
+//!             button {
+//!                 onclick: move |_| println!("{}", recorder.trace().to_json().unwrap()),
+//!                 "Export trace"
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`Gestures::recorder`]: crate::use_gestures::Gestures::recorder
+//! [`GestureHarness::replay`]: crate::testing::GestureHarness::replay
+
+use std::{cell::RefCell, rc::Rc};
+
+use dioxus::html::{
+    input_data::{encode_mouse_button_set, MouseButton},
+    point_interaction::{
+        InteractionElementOffset, InteractionLocation, ModifiersInteraction, PointerInteraction,
+    },
+    PointerData,
+};
+use serde::{Deserialize, Serialize};
+use web_time::Instant;
+
+use crate::state::events::PointerEventKind;
+
+/// The pointer events of a target in the order they were received.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PointerTrace {
+    pub events: Vec<TracedEvent>,
+}
+
+/// A pointer event with all values of its [`PointerData`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TracedEvent {
+    /// Milliseconds since the recording started.
+    pub time: f64,
+    pub kind: PointerEventKind,
+    pub pointer_id: i32,
+    pub pointer_type: String,
+    pub is_primary: bool,
+    pub client: (f64, f64),
+    pub screen: (f64, f64),
+    pub page: (f64, f64),
+    pub element: (f64, f64),
+    pub width: f64,
+    pub height: f64,
+    pub pressure: f32,
+    pub tangential_pressure: f32,
+    pub tilt_x: i32,
+    pub tilt_y: i32,
+    pub twist: i32,
+    /// The `button` of the DOM event, if a button changed.
+    pub button: Option<i16>,
+    /// The `buttons` bit mask of the DOM event.
+    pub buttons: u16,
+    /// The bits of [`Modifiers`](dioxus::prelude::Modifiers).
+    pub modifiers: u32,
+}
+
+impl TracedEvent {
+    pub fn new(time: f64, kind: PointerEventKind, data: &PointerData) -> Self {
+        let client = data.client_coordinates();
+        let screen = data.screen_coordinates();
+        let page = data.page_coordinates();
+        let element = data.element_coordinates();
+        Self {
+            time,
+            kind,
+            pointer_id: data.pointer_id(),
+            pointer_type: data.pointer_type(),
+            is_primary: data.is_primary(),
+            client: (client.x, client.y),
+            screen: (screen.x, screen.y),
+            page: (page.x, page.y),
+            element: (element.x, element.y),
+            width: data.width(),
+            height: data.height(),
+            pressure: data.pressure(),
+            tangential_pressure: data.tangential_pressure(),
+            tilt_x: data.tilt_x(),
+            tilt_y: data.tilt_y(),
+            twist: data.twist(),
+            button: data.trigger_button().map(MouseButton::into_web_code),
+            buttons: encode_mouse_button_set(data.held_buttons()),
+            modifiers: data.modifiers().bits(),
+        }
+    }
+}
+
+impl PointerTrace {
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Records the pointer events of the targets it is added to.
+///
+/// Only the values of the events themselves are recorded. The frame of the
+/// target the local coordinates are mapped through, and the coalesced and
+/// predicted samples of an event, are not part of a [`TracedEvent`]. A replay
+/// maps through the frame of the element coordinates and hands out each event
+/// as its only sample.
+#[derive(Clone)]
+pub struct GestureRecorder {
+    start: Instant,
+    trace: Rc<RefCell<PointerTrace>>,
+}
+
+impl GestureRecorder {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            trace: Rc::default(),
+        }
+    }
+
+    pub(crate) fn record(&self, kind: PointerEventKind, data: &PointerData) {
+        let time = self.start.elapsed().as_secs_f64() * 1000.0;
+        self.trace
+            .borrow_mut()
+            .events
+            .push(TracedEvent::new(time, kind, data));
+    }
+
+    pub fn trace(&self) -> PointerTrace {
+        self.trace.borrow().clone()
+    }
+
+    /// Returns the recorded trace and clears it.
+    pub fn take(&self) -> PointerTrace {
+        std::mem::take(&mut *self.trace.borrow_mut())
+    }
+}
+
+impl Default for GestureRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use dioxus::{html::PointerData, prelude::Event};
+
+    use crate::{
+        state::{
+            events::PointerEventReceiver,
+            gestures::{drag::Drag, hover::Hover, pinch::Pinch},
+            recognizer::GestureRecognizer,
+        },
+        testing::{
+            CallbackLog, GestureHarness, PointerEventKind, PointerSequence, SyntheticPointer,
+        },
+        trace::{GestureRecorder, PointerTrace, TracedEvent},
+        use_gestures::Gestures,
+    };
+
+    const DRAG_THEN_PINCH: &str = include_str!("../fixtures/traces/drag_then_pinch.json");
+
+    fn gestures(log: &CallbackLog) -> Gestures {
+        Gestures::default()
+            .hover(log.hover(Hover::default()))
+            .drag(log.drag(Drag::default()))
+            .pinch(log.pinch(Pinch::default()))
+    }
+
+    #[test]
+    fn replay_reproduces_the_recorded_callbacks() {
+        let recorded = CallbackLog::default();
+        let recorder = GestureRecorder::new();
+        let mut harness = GestureHarness::new(gestures(&recorded).recorder(recorder.clone()));
+        harness.play(
+            PointerSequence::default()
+                .down(SyntheticPointer::pen(3).pressure(0.3))
+                .move_by_steps(3, (25.0, 10.0), 5)
+                .down(SyntheticPointer::touch(4).at(80.0, 0.0))
+                .move_by_steps(4, (50.0, 40.0), 3)
+                .cancel(4)
+                .up(3),
+        );
+
+        let trace = PointerTrace::from_json(&recorder.trace().to_json().unwrap()).unwrap();
+        assert_eq!(trace, recorder.trace());
+
+        let replayed = CallbackLog::default();
+        GestureHarness::new(gestures(&replayed)).replay(&trace);
+        assert_eq!(replayed.entries(), recorded.entries());
+    }
+
+    #[test]
+    fn drag_then_pinch() {
+        let log = CallbackLog::default();
+        GestureHarness::new(
+            Gestures::default()
+                .drag(log.drag(Drag::default()))
+                .pinch(log.pinch(Pinch::default())),
+        )
+        .replay(&PointerTrace::from_json(DRAG_THEN_PINCH).unwrap());
+
+        log.assert_order(&[
+            "drag.start",
+            "drag.update",
+            "drag.end",
+            "pinch.start",
+            "pinch.update",
+            "pinch.end",
+        ]);
+    }
+
+    /// Counts presses held for 500ms.
+    struct LongPress {
+        press: u64,
+        pressed: bool,
+        timeouts: Vec<(f64, u64)>,
+        fired: Rc<Cell<usize>>,
+    }
+
+    impl PointerEventReceiver<&Event<PointerData>> for LongPress {
+        fn pointer_over(&mut self, _: &Event<PointerData>) {}
+        fn pointer_enter(&mut self, _: &Event<PointerData>) {}
+        fn pointer_down(&mut self, _: &Event<PointerData>) {
+            self.press += 1;
+            self.pressed = true;
+            self.timeouts.push((500.0, self.press));
+        }
+        fn pointer_move(&mut self, _: &Event<PointerData>) {}
+        fn pointer_up(&mut self, _: &Event<PointerData>) {
+            self.pressed = false;
+        }
+        fn pointer_cancel(&mut self, _: &Event<PointerData>) {}
+        fn pointer_out(&mut self, _: &Event<PointerData>) {}
+        fn pointer_leave(&mut self, _: &Event<PointerData>) {}
+    }
+
+    impl GestureRecognizer for LongPress {
+        fn take_timeouts(&mut self) -> Vec<(f64, u64)> {
+            std::mem::take(&mut self.timeouts)
+        }
+
+        fn timeout(&mut self, token: u64) {
+            if self.pressed && token == self.press {
+                self.fired.set(self.fired.get() + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn replay_keeps_the_time_between_events() {
+        let press = |release: f64| {
+            let pointer = SyntheticPointer::touch(1).at(10.0, 10.0);
+            let event = |time, kind| TracedEvent::new(time, kind, &pointer.to_event().data());
+            PointerTrace {
+                events: vec![
+                    event(1000.0, PointerEventKind::Down),
+                    event(1000.0 + release, PointerEventKind::Up),
+                ],
+            }
+        };
+        let fired = Rc::new(Cell::new(0));
+        let mut harness = GestureHarness::new(Gestures::default().recognizer(
+            "long_press",
+            LongPress {
+                press: 0,
+                pressed: false,
+                timeouts: Vec::new(),
+                fired: Rc::clone(&fired),
+            },
+        ));
+
+        harness.replay(&press(200.0));
+        assert_eq!(fired.get(), 0);
+        harness.replay(&press(600.0));
+        assert_eq!(fired.get(), 1);
+    }
+}
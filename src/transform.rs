@@ -0,0 +1,260 @@
+//! Accumulation of drag and pinch updates into an affine transform.
+
+use dioxus::html::geometry::euclid::{Angle, Transform2D};
+
+use crate::state::{
+    frame::{LocalPoint, LocalSpace, LocalVector},
+    gestures::{drag::DragUpdateData, pinch::PinchUpdateData},
+};
+
+/// A pan, zoom and rotation accumulated from the updates of drag and pinch
+/// gestures, in the local coordinates of their target.
+///
+/// Pinch updates scale and rotate about the pinch center, so the content under
+/// the fingers stays under the fingers. The transform is meant for content of
+/// the target, positioned at its origin with `transform-origin: 0 0`, since
+/// the target itself must not move while its local coordinates are measured.
+///
+/// ```rust
+/// use dioxus::prelude::*;
+/// use dioxus_gestures::{
+///     state::gestures::{drag::Drag, pinch::Pinch},
+///     transform::GestureTransform,
+///     use_gestures::{use_gestures, Gestures},
+/// };
+///
+/// #[component]
+/// pub fn Zoomable() -> Element {
+///     let mut transform = use_signal(|| GestureTransform::default().min_scale(0.5).max_scale(4.0));
+///
+///     let gestures = use_gestures(
+///         Gestures::default()
+///             .drag(Drag::default().on_update(move |data| transform.write().apply_drag(&data)))
+///             .pinch(Pinch::default().on_update(move |data| transform.write().apply_pinch(&data))),
+///     );
+///
+///     rsx! {
+///         div {
+///             style: "touch-action: none; overflow: hidden;",
+///             ..gestures.event_handlers(),
+///             div {
+///                 style: "transform-origin: 0 0; transform: {transform.read().to_css_matrix()};",
+///                 "Content"
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GestureTransform {
+    /// Maps content coordinates to local coordinates of the target.
+    pub transform: Transform2D<f64, LocalSpace, LocalSpace>,
+    pub min_scale: f64,
+    pub max_scale: f64,
+}
+
+impl GestureTransform {
+    pub fn min_scale(mut self, min_scale: f64) -> Self {
+        self.min_scale = min_scale;
+        self
+    }
+
+    pub fn max_scale(mut self, max_scale: f64) -> Self {
+        self.max_scale = max_scale;
+        self
+    }
+}
+
+impl GestureTransform {
+    pub fn scale(&self) -> f64 {
+        self.transform.m11.hypot(self.transform.m12)
+    }
+
+    pub fn rotation(&self) -> Angle<f64> {
+        Angle::radians(self.transform.m12.atan2(self.transform.m11))
+    }
+
+    pub fn translation(&self) -> LocalVector {
+        LocalVector::new(self.transform.m31, self.transform.m32)
+    }
+
+    /// The content coordinates of a local point, if the transform can be
+    /// inverted.
+    pub fn content_point(&self, point: LocalPoint) -> Option<LocalPoint> {
+        Some(self.transform.inverse()?.transform_point(point))
+    }
+
+    pub fn pan(&mut self, movement: LocalVector) {
+        self.transform = self.transform.then_translate(movement);
+    }
+
+    /// Scales by `factor` about `center`, limited to the scale bounds.
+    pub fn zoom_about(&mut self, center: LocalPoint, factor: f64) {
+        self.transform_about(center, self.clamp_factor(factor), Angle::zero());
+    }
+
+    pub fn rotate_about(&mut self, center: LocalPoint, angle: Angle<f64>) {
+        self.transform_about(center, 1.0, angle);
+    }
+
+    pub fn apply_drag(&mut self, data: &DragUpdateData) {
+        self.pan(data.pointer.delta_local_movement());
+    }
+
+    /// Moves along with the pinch center, then scales and rotates about it.
+    pub fn apply_pinch(&mut self, data: &PinchUpdateData) {
+        self.pan(data.delta_local_movement());
+        self.transform_about(
+            data.current_local_center(),
+            self.clamp_factor(data.delta_distance_scale()),
+            data.delta_angle(),
+        );
+    }
+
+    /// The transform as a CSS `matrix()`.
+    pub fn to_css_matrix(&self) -> String {
+        let t = &self.transform;
+        format!(
+            "matrix({}, {}, {}, {}, {}, {})",
+            t.m11, t.m12, t.m21, t.m22, t.m31, t.m32
+        )
+    }
+
+    fn clamp_factor(&self, factor: f64) -> f64 {
+        let scale = self.scale();
+        if !factor.is_finite() || scale == 0.0 {
+            return 1.0;
+        }
+        let (min, max) = self.scale_bounds();
+        (scale * factor).clamp(min, max) / scale
+    }
+
+    /// The scale bounds in order, as swapped bounds would make `clamp` panic.
+    fn scale_bounds(&self) -> (f64, f64) {
+        (
+            self.min_scale.min(self.max_scale),
+            self.max_scale.max(self.min_scale),
+        )
+    }
+
+    fn transform_about(&mut self, center: LocalPoint, factor: f64, angle: Angle<f64>) {
+        self.transform = self
+            .transform
+            .then_translate(-center.to_vector())
+            .then_scale(factor, factor)
+            .then_rotate(angle)
+            .then_translate(center.to_vector());
+    }
+}
+
+impl Default for GestureTransform {
+    fn default() -> Self {
+        Self {
+            transform: Transform2D::identity(),
+            min_scale: 0.0,
+            max_scale: f64::INFINITY,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use dioxus::html::geometry::euclid::Angle;
+
+    use crate::{
+        state::{
+            frame::{LocalPoint, LocalVector},
+            gestures::{drag::Drag, pinch::Pinch},
+        },
+        testing::{GestureHarness, PointerSequence, SyntheticPointer},
+        transform::GestureTransform,
+        use_gestures::Gestures,
+    };
+
+    fn play(transform: GestureTransform, sequence: PointerSequence) -> GestureTransform {
+        let transform = Rc::new(RefCell::new(transform));
+        let mut harness = GestureHarness::new(
+            Gestures::default()
+                .drag(Drag::default().on_update({
+                    let transform = Rc::clone(&transform);
+                    move |data| transform.borrow_mut().apply_drag(&data)
+                }))
+                .pinch(Pinch::default().has_started(|_, _| true).on_update({
+                    let transform = Rc::clone(&transform);
+                    move |data| transform.borrow_mut().apply_pinch(&data)
+                })),
+        );
+        harness.play(sequence);
+        let transform = *transform.borrow();
+        transform
+    }
+
+    fn assert_near(a: LocalPoint, b: LocalPoint) {
+        assert!((a - b).length() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn pinch_scales_about_its_center() {
+        let transform = play(
+            GestureTransform::default(),
+            PointerSequence::pinch((100.0, 100.0), 50.0, 150.0, 4),
+        );
+
+        assert!((transform.scale() - 3.0).abs() < 1e-9);
+        let t = transform.transform;
+        assert_near(
+            t.transform_point(LocalPoint::new(100.0, 100.0)),
+            LocalPoint::new(100.0, 100.0),
+        );
+        assert_near(
+            t.transform_point(LocalPoint::new(75.0, 100.0)),
+            LocalPoint::new(25.0, 100.0),
+        );
+    }
+
+    #[test]
+    fn scale_is_limited() {
+        let transform = play(
+            GestureTransform::default().max_scale(2.0),
+            PointerSequence::pinch((100.0, 100.0), 50.0, 150.0, 4),
+        );
+        assert!((transform.scale() - 2.0).abs() < 1e-9);
+
+        let mut transform = GestureTransform::default().min_scale(0.5);
+        transform.zoom_about(LocalPoint::zero(), 0.1);
+        assert!((transform.scale() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn swapped_scale_bounds_do_not_panic() {
+        let mut transform = GestureTransform::default().min_scale(5.0).max_scale(2.0);
+        transform.zoom_about(LocalPoint::zero(), 10.0);
+        assert!((transform.scale() - 5.0).abs() < 1e-9);
+        transform.zoom_about(LocalPoint::zero(), 0.1);
+        assert!((transform.scale() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn drag_pans() {
+        let transform = play(
+            GestureTransform::default(),
+            PointerSequence::drag(SyntheticPointer::touch(1), (30.0, 40.0), 5),
+        );
+        // The movement up to the drag threshold is not part of any update.
+        assert_eq!(transform.translation(), LocalVector::new(24.0, 32.0));
+    }
+
+    #[test]
+    fn renders_a_css_matrix() {
+        let mut transform = GestureTransform::default();
+        transform.zoom_about(LocalPoint::new(10.0, 10.0), 2.0);
+        transform.pan(LocalVector::new(5.0, 0.0));
+        assert_eq!(transform.to_css_matrix(), "matrix(2, 0, 0, 2, -5, -10)");
+
+        transform.rotate_about(LocalPoint::zero(), Angle::degrees(90.0));
+        assert!((transform.rotation().to_degrees() - 90.0).abs() < 1e-9);
+        assert!((transform.scale() - 2.0).abs() < 1e-9);
+    }
+}
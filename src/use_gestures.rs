@@ -5,22 +5,52 @@ use std::{cell::RefCell, rc::Rc};
 use dioxus::{
     core::{AttributeValue, Event, ListenerCallback},
     html::PlatformEventData,
-    prelude::{use_hook, Attribute},
+    prelude::{try_consume_context, use_hook, Attribute},
 };
 
 #[cfg(feature = "fullstack")]
 use dioxus::prelude::use_server_cached;
 
+#[cfg(feature = "web")]
+use dioxus::html::MountedData;
+
+#[cfg(feature = "debug")]
+use dioxus::prelude::Signal;
+
 use crate::state::{
+    arbitration::Arbitration,
+    arena::GestureArena,
     events::PointerEventReceiver,
-    gestures::{drag::Drag, hover::Hover, pinch::Pinch},
+    gestures::{
+        context_menu::{ContextMenu, ContextMenuGestureState},
+        down_pointer::DownPointerGestureState,
+        drag::Drag,
+        hover::{Hover, HoverGestureState},
+        pinch::Pinch,
+        rotate::Rotate,
+        scale::Scale,
+        stroke::Stroke,
+    },
     options::UseGesturesOptions,
+    recognizer::{GestureRecognizer, NamedRecognizer},
+    schedule,
 };
 use crate::state::{external_handlers::ExternalHandlers, state::UseGesturesState};
+#[cfg(any(test, feature = "trace"))]
+use crate::trace::GestureRecorder;
 
 #[derive(Clone)]
 pub struct UseGestures {
-    state: Rc<RefCell<UseGesturesState>>,
+    pub(crate) state: Rc<RefCell<UseGesturesState>>,
+    /// Whether the native `contextmenu` of the target is prevented, see
+    /// [`ContextMenu::suppress_native_menu`].
+    suppress_context_menu: bool,
+}
+
+impl PartialEq for UseGestures {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.state, &other.state)
+    }
 }
 
 impl UseGestures {
@@ -30,17 +60,77 @@ impl UseGestures {
             hover,
             drag,
             pinch,
+            stroke,
+            rotate,
+            scale,
+            context_menu,
+            recognizers,
+            arbitration,
+            arena,
             options,
+            #[cfg(any(test, feature = "trace"))]
+            recorder,
         } = config;
+        let builtin: [NamedRecognizer; 3] = [
+            (
+                "hover",
+                Rc::new(RefCell::new(HoverGestureState::new(hover))),
+            ),
+            (
+                "drag",
+                Rc::new(RefCell::new(DownPointerGestureState::new(drag))),
+            ),
+            (
+                "pinch",
+                Rc::new(RefCell::new(DownPointerGestureState::new(pinch))),
+            ),
+        ];
+        let suppress_context_menu = context_menu
+            .as_ref()
+            .is_some_and(|context_menu| context_menu.suppress_native_menu);
+        let optional: [Option<NamedRecognizer>; 4] = [
+            stroke.map(|stroke| -> NamedRecognizer {
+                (
+                    "stroke",
+                    Rc::new(RefCell::new(DownPointerGestureState::new(stroke))),
+                )
+            }),
+            rotate.map(|rotate| -> NamedRecognizer {
+                (
+                    "rotate",
+                    Rc::new(RefCell::new(DownPointerGestureState::new(rotate))),
+                )
+            }),
+            scale.map(|scale| -> NamedRecognizer {
+                (
+                    "scale",
+                    Rc::new(RefCell::new(DownPointerGestureState::new(scale))),
+                )
+            }),
+            context_menu.map(|context_menu| -> NamedRecognizer {
+                let state = ContextMenuGestureState::new(context_menu);
+                ("context_menu", Rc::new(RefCell::new(state)))
+            }),
+        ];
+        #[allow(unused_mut)]
+        let mut state = UseGesturesState::new(
+            target_id,
+            external_handlers,
+            builtin
+                .into_iter()
+                .chain(optional.into_iter().flatten())
+                .chain(recognizers)
+                .collect(),
+            arbitration,
+            arena,
+            options,
+        );
+        #[cfg(any(test, feature = "trace"))]
+        state.set_recorder(recorder);
+
         Self {
-            state: Rc::new(RefCell::new(UseGesturesState::new(
-                target_id,
-                external_handlers,
-                hover,
-                drag,
-                pinch,
-                options,
-            ))),
+            state: Rc::new(RefCell::new(state)),
+            suppress_context_menu,
         }
     }
 
@@ -52,9 +142,21 @@ impl UseGestures {
                     paste::paste! { stringify!([<$attribute_name:camel:lower>])},
                     AttributeValue::Listener(
                         ListenerCallback::new(move |e: Event<PlatformEventData>| {
-                            let _ = pointer_ref
+                            let request_frame = pointer_ref
                                 .try_borrow_mut()
-                                .map(|mut s| s.$function_name(e.map(|data| data.into())));
+                                .map(|mut s| {
+                                    s.$function_name(e.map(|data| data.into()));
+                                    s.request_frame()
+                                })
+                                .unwrap_or(false);
+                            UseGesturesState::schedule_timeouts(&pointer_ref);
+                            if request_frame {
+                                let pointer_ref = Rc::clone(&pointer_ref);
+                                schedule::request_animation_frame(move || {
+                                    let _ =
+                                        pointer_ref.try_borrow_mut().map(|mut s| s.flush_updates());
+                                });
+                            }
                         })
                         .erase(),
                     ),
@@ -64,7 +166,8 @@ impl UseGestures {
             }};
         }
 
-        vec![
+        #[allow(unused_mut)]
+        let mut handlers = vec![
             Attribute::new(
                 self.state.borrow().options.target_id_attribute_name,
                 AttributeValue::Text(self.state.borrow().target_id.clone()),
@@ -79,20 +182,62 @@ impl UseGestures {
             pointer_event_handler!(on_pointer_cancel, pointer_cancel),
             pointer_event_handler!(on_pointer_out, pointer_out),
             pointer_event_handler!(on_pointer_leave, pointer_leave),
-        ]
+            pointer_event_handler!(on_got_pointer_capture, got_pointer_capture),
+            pointer_event_handler!(on_lost_pointer_capture, lost_pointer_capture),
+        ];
+
+        if self.suppress_context_menu {
+            handlers.push(Attribute::new(
+                "oncontextmenu",
+                AttributeValue::Listener(
+                    ListenerCallback::new(|e: Event<PlatformEventData>| e.prevent_default())
+                        .erase(),
+                ),
+                None,
+                false,
+            ));
+        }
+
+        #[cfg(feature = "web")]
+        handlers.push({
+            let state = Rc::clone(&self.state);
+            Attribute::new(
+                "onmounted",
+                AttributeValue::Listener(
+                    ListenerCallback::new(move |e: Event<PlatformEventData>| {
+                        let _ = state
+                            .try_borrow_mut()
+                            .map(|mut s| s.mounted(&MountedData::from(&*e.data())));
+                    })
+                    .erase(),
+                ),
+                None,
+                false,
+            )
+        });
+
+        handlers
     }
 }
 
 pub fn use_gestures(props: Gestures) -> UseGestures {
     #[cfg(not(feature = "fullstack"))]
-    let target_id =
-        use_hook(|| props.options.target_id.clone().unwrap_or_else(|| nanoid!()));
+    let target_id = use_hook(|| props.options.target_id.clone().unwrap_or_else(|| nanoid!()));
 
     #[cfg(feature = "fullstack")]
     let target_id =
         use_server_cached(|| props.options.target_id.clone().unwrap_or_else(|| nanoid!()));
-        
-    use_hook(|| UseGestures::new(target_id, props))
+
+    use_hook(|| {
+        let arena = props
+            .arena
+            .clone()
+            .or_else(try_consume_context::<GestureArena>);
+        let gestures = UseGestures::new(target_id, props.arena(arena));
+        #[cfg(feature = "debug")]
+        gestures.state.borrow_mut().set_revision(Signal::new(0));
+        gestures
+    })
 }
 
 #[derive(Default)]
@@ -101,7 +246,18 @@ pub struct Gestures {
     pub hover: Hover,
     pub drag: Drag,
     pub pinch: Pinch,
+    /// Only recognized if set, as it would otherwise take precedence over
+    /// [`Drag`].
+    pub stroke: Option<Stroke>,
+    pub rotate: Option<Rotate>,
+    pub scale: Option<Scale>,
+    pub context_menu: Option<ContextMenu>,
+    pub recognizers: Vec<NamedRecognizer>,
+    pub arbitration: Arbitration,
+    pub arena: Option<GestureArena>,
     pub options: UseGesturesOptions,
+    #[cfg(any(test, feature = "trace"))]
+    pub recorder: Option<GestureRecorder>,
 }
 
 impl Gestures {
@@ -125,8 +281,55 @@ impl Gestures {
         self
     }
 
+    pub fn stroke(mut self, stroke: Stroke) -> Self {
+        self.stroke = Some(stroke);
+        self
+    }
+
+    pub fn rotate(mut self, rotate: Rotate) -> Self {
+        self.rotate = Some(rotate);
+        self
+    }
+
+    pub fn scale(mut self, scale: Scale) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    pub fn context_menu(mut self, context_menu: ContextMenu) -> Self {
+        self.context_menu = Some(context_menu);
+        self
+    }
+
+    pub fn recognizer(
+        mut self,
+        name: &'static str,
+        recognizer: impl GestureRecognizer + 'static,
+    ) -> Self {
+        self.recognizers
+            .push((name, Rc::new(RefCell::new(recognizer))));
+        self
+    }
+
+    pub fn arbitration(mut self, arbitration: Arbitration) -> Self {
+        self.arbitration = arbitration;
+        self
+    }
+
+    pub fn arena(mut self, arena: Option<GestureArena>) -> Self {
+        self.arena = arena;
+        self
+    }
+
     pub fn options(mut self, options: UseGesturesOptions) -> Self {
         self.options = options;
         self
     }
+
+    /// Records the pointer events of the target, see [`GestureRecorder`].
+    #[cfg(any(test, feature = "trace"))]
+    pub fn recorder(mut self, recorder: GestureRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
 }
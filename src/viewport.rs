@@ -0,0 +1,675 @@
+//! A pannable and zoomable viewport, enabled with the `viewport` feature.
+//!
+//! Children of a [`PanZoomViewport`] are rendered inside a transformed
+//! container. Dragging pans, pinching and ctrl+wheel zoom about the focal
+//! point, and a double tap zooms in, or back out once the maximum zoom is
+//! reached. The zoom is kept within its bounds and, if the size of the content
+//! is given, the content is kept in view.
+//!
+//! Pivot points are local coordinates of the viewport. Without the `web`
+//! feature, they are relative to the element an event was fired on rather than
+//! the viewport, see [`TargetFrame::from_event`].
+//!
+//! ```rust
+//! use dioxus::prelude::*;
+//! use dioxus_gestures::viewport::{use_pan_zoom, PanZoom, PanZoomViewport};
+//!
+//! #[component]
+//! pub fn ImageViewer() -> Element {
+//!     let mut pan_zoom = use_pan_zoom(
+//!         PanZoom::default()
+//!             .min_zoom(0.25)
+//!             .max_zoom(8.0)
+//!             .content_size(1600.0, 1200.0),
+//!     );
+//!
+//!     rsx! {
+//!         button { onclick: move |_| pan_zoom.fit(), "Fit" }
+//!         button { onclick: move |_| pan_zoom.zoom_to(1.0, None), "100%" }
+//!         div {
+//!             style: "width: 800px; height: 600px;",
+//!             PanZoomViewport {
+//!                 pan_zoom,
+//!                 img { src: "map.png", width: 1600, height: 1200, draggable: false }
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`TargetFrame::from_event`]: crate::state::frame::TargetFrame::from_event
+
+use std::rc::Rc;
+
+use dioxus::{
+    html::{
+        geometry::{
+            euclid::{Size2D, Transform2D},
+            ClientPoint, WheelDelta,
+        },
+        point_interaction::{InteractionElementOffset, InteractionLocation, ModifiersInteraction},
+        MountedData, PointerData, WheelData,
+    },
+    prelude::*,
+};
+
+use crate::{
+    state::{
+        events::PointerEventReceiver,
+        frame::{FrameSource, LocalPoint, LocalSpace, LocalVector, TargetFrame},
+        gestures::{
+            drag::Drag,
+            pinch::{Pinch, PinchUpdateData},
+            pointer::PointerId,
+        },
+        recognizer::GestureRecognizer,
+    },
+    transform::GestureTransform,
+    use_gestures::{use_gestures, Gestures},
+};
+
+const DOUBLE_TAP_INTERVAL: f64 = 300.0;
+const DOUBLE_TAP_DISTANCE: f64 = 30.0;
+const TAP_SLOP: f64 = 10.0;
+
+/// The configuration of a [`PanZoomViewport`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PanZoom {
+    pub min_zoom: f64,
+    pub max_zoom: f64,
+    /// The untransformed size of the content, which is kept in view if given.
+    pub content_size: Option<Size2D<f64, LocalSpace>>,
+    /// The zoom factor of a double tap.
+    pub double_tap_zoom: f64,
+    /// The zoom factor per pixel of ctrl+wheel scrolling, as an exponent.
+    pub wheel_zoom_speed: f64,
+}
+
+impl PanZoom {
+    pub fn min_zoom(mut self, min_zoom: f64) -> Self {
+        self.min_zoom = min_zoom;
+        self
+    }
+
+    pub fn max_zoom(mut self, max_zoom: f64) -> Self {
+        self.max_zoom = max_zoom;
+        self
+    }
+
+    pub fn content_size(mut self, width: f64, height: f64) -> Self {
+        self.content_size = Some(Size2D::new(width, height));
+        self
+    }
+
+    pub fn double_tap_zoom(mut self, factor: f64) -> Self {
+        self.double_tap_zoom = factor;
+        self
+    }
+
+    pub fn wheel_zoom_speed(mut self, speed: f64) -> Self {
+        self.wheel_zoom_speed = speed;
+        self
+    }
+}
+
+impl PanZoom {
+    /// The zoom bounds in order, as swapped bounds would make `clamp` panic.
+    fn zoom_bounds(&self) -> (f64, f64) {
+        (
+            self.min_zoom.min(self.max_zoom),
+            self.max_zoom.max(self.min_zoom),
+        )
+    }
+}
+
+impl Default for PanZoom {
+    fn default() -> Self {
+        Self {
+            min_zoom: 0.1,
+            max_zoom: 10.0,
+            content_size: None,
+            double_tap_zoom: 2.0,
+            wheel_zoom_speed: 0.002,
+        }
+    }
+}
+
+/// The transform of a viewport and the sizes it is bounded by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PanZoomState {
+    pub config: PanZoom,
+    pub transform: GestureTransform,
+    pub viewport_size: Size2D<f64, LocalSpace>,
+}
+
+impl PanZoomState {
+    pub fn new(config: PanZoom) -> Self {
+        Self {
+            config,
+            transform: GestureTransform::default()
+                .min_scale(config.min_zoom)
+                .max_scale(config.max_zoom),
+            viewport_size: Size2D::zero(),
+        }
+    }
+
+    pub fn zoom(&self) -> f64 {
+        self.transform.scale()
+    }
+
+    pub fn pan_by(&mut self, movement: LocalVector) {
+        self.transform.pan(movement);
+        self.keep_in_view();
+    }
+
+    /// Scales by `factor` about `center`, or about the center of the viewport.
+    pub fn zoom_by(&mut self, factor: f64, center: Option<LocalPoint>) {
+        let center = center.unwrap_or_else(|| self.viewport_size.to_vector().to_point() / 2.0);
+        self.transform.zoom_about(center, factor);
+        self.keep_in_view();
+    }
+
+    pub fn zoom_to(&mut self, zoom: f64, center: Option<LocalPoint>) {
+        self.zoom_by(zoom / self.zoom(), center);
+    }
+
+    /// Zooms the content to fit the viewport and centers it, or resets the
+    /// transform if the size of the content is unknown.
+    pub fn fit(&mut self) {
+        let Some(content) = self.config.content_size.filter(|size| !size.is_empty()) else {
+            self.reset();
+            return;
+        };
+        let (min_zoom, max_zoom) = self.config.zoom_bounds();
+        let zoom = (self.viewport_size.width / content.width)
+            .min(self.viewport_size.height / content.height)
+            .clamp(min_zoom, max_zoom);
+        let offset = (self.viewport_size - content * zoom) / 2.0;
+        self.transform.transform = Transform2D::scale(zoom, zoom)
+            .then_translate(LocalVector::new(offset.width, offset.height));
+    }
+
+    pub fn reset(&mut self) {
+        self.transform.transform = Transform2D::identity();
+        self.keep_in_view();
+    }
+
+    /// Zooms in about `point`, or fits the content once zooming in further
+    /// would exceed the maximum zoom.
+    pub fn double_tap(&mut self, point: LocalPoint) {
+        let (_, max_zoom) = self.config.zoom_bounds();
+        if self.zoom() * self.config.double_tap_zoom > max_zoom + f64::EPSILON {
+            self.fit();
+        } else {
+            self.zoom_by(self.config.double_tap_zoom, Some(point));
+        }
+    }
+
+    /// Moves along with the pinch center and zooms about it.
+    pub fn apply_pinch(&mut self, data: &PinchUpdateData) {
+        self.pan_by(data.delta_local_movement());
+        self.zoom_by(
+            data.delta_distance_scale(),
+            Some(data.current_local_center()),
+        );
+    }
+
+    /// Zooms about `point` for a wheel movement of `delta` pixels.
+    pub fn wheel(&mut self, point: LocalPoint, delta: f64) {
+        self.zoom_by((-delta * self.config.wheel_zoom_speed).exp(), Some(point));
+    }
+
+    /// Moves the content so that it covers the viewport, or lies within it if
+    /// it is smaller.
+    fn keep_in_view(&mut self) {
+        let Some(content) = self.config.content_size else {
+            return;
+        };
+        let content = content * self.zoom();
+        let clamp = |offset: f64, content: f64, viewport: f64| {
+            let (min, max) = match content >= viewport {
+                true => (viewport - content, 0.0),
+                false => (0.0, viewport - content),
+            };
+            offset.clamp(min, max)
+        };
+        let transform = &mut self.transform.transform;
+        transform.m31 = clamp(transform.m31, content.width, self.viewport_size.width);
+        transform.m32 = clamp(transform.m32, content.height, self.viewport_size.height);
+    }
+}
+
+/// The handle of a [`PanZoomViewport`], returned by [`use_pan_zoom`].
+#[derive(Clone, Copy, PartialEq)]
+pub struct UsePanZoom {
+    pub state: Signal<PanZoomState>,
+}
+
+impl UsePanZoom {
+    pub fn transform(&self) -> GestureTransform {
+        self.state.read().transform
+    }
+
+    pub fn zoom(&self) -> f64 {
+        self.state.read().zoom()
+    }
+
+    pub fn pan_by(&mut self, movement: LocalVector) {
+        self.state.write().pan_by(movement);
+    }
+
+    pub fn zoom_by(&mut self, factor: f64, center: Option<LocalPoint>) {
+        self.state.write().zoom_by(factor, center);
+    }
+
+    pub fn zoom_to(&mut self, zoom: f64, center: Option<LocalPoint>) {
+        self.state.write().zoom_to(zoom, center);
+    }
+
+    pub fn fit(&mut self) {
+        self.state.write().fit();
+    }
+
+    pub fn reset(&mut self) {
+        self.state.write().reset();
+    }
+}
+
+pub fn use_pan_zoom(config: PanZoom) -> UsePanZoom {
+    use_hook(|| UsePanZoom {
+        state: Signal::new(PanZoomState::new(config)),
+    })
+}
+
+/// Renders its children in a container that is panned and zoomed by gestures
+/// on the viewport. The viewport fills its parent.
+#[component]
+pub fn PanZoomViewport(pan_zoom: UsePanZoom, children: Element) -> Element {
+    let mut state = pan_zoom.state;
+    let mut mounted = use_signal(|| None::<Rc<MountedData>>);
+
+    let gestures = use_gestures(
+        Gestures::default()
+            .drag(Drag::default().on_update(move |data| {
+                state.write().pan_by(data.pointer.delta_local_movement());
+            }))
+            .pinch(Pinch::default().on_update(move |data| state.write().apply_pinch(&data)))
+            .recognizer(
+                "double_tap",
+                DoubleTap::new(move |point| state.write().double_tap(point)),
+            ),
+    );
+    let handlers = gestures.event_handlers();
+    let transform = state.read().transform.to_css_matrix();
+
+    rsx! {
+        div {
+            style: "
+                position: relative;
+                width: 100%;
+                height: 100%;
+                overflow: hidden;
+                touch-action: none;
+                user-select: none;
+            ",
+            ..handlers,
+            div {
+                style: "position: absolute; inset: 0;",
+                onmounted: move |event| {
+                    let data = event.data();
+                    mounted.set(Some(Rc::clone(&data)));
+                    spawn(async move {
+                        if let Ok(rect) = data.get_client_rect().await {
+                            state.write().viewport_size = rect.size.cast_unit();
+                        }
+                    });
+                },
+                onresize: move |event| {
+                    if let Ok(size) = event.get_border_box_size() {
+                        state.write().viewport_size = size.cast_unit();
+                    }
+                },
+                onwheel: move |event| {
+                    if !event.modifiers().ctrl() {
+                        return;
+                    }
+                    event.prevent_default();
+                    let delta = match event.delta() {
+                        WheelDelta::Pixels(delta) => delta.y,
+                        WheelDelta::Lines(delta) => delta.y * 16.0,
+                        WheelDelta::Pages(delta) => delta.y * state.read().viewport_size.height,
+                    };
+                    let point = wheel_point(mounted.peek().as_deref(), &event.data());
+                    state.write().wheel(point, delta);
+                },
+                div {
+                    style: "
+                        position: absolute;
+                        left: 0;
+                        top: 0;
+                        transform-origin: 0 0;
+                        transform: {transform};
+                    ",
+                    {children}
+                }
+            }
+        }
+    }
+}
+
+/// The local coordinates of a wheel event. The viewport is measured when the
+/// event is handled with the `web` feature, otherwise the coordinates are
+/// relative to the element the event was fired on.
+#[cfg_attr(not(feature = "web"), allow(unused_variables))]
+fn wheel_point(viewport: Option<&MountedData>, data: &WheelData) -> LocalPoint {
+    #[cfg(feature = "web")]
+    if let Some(element) = viewport.and_then(MountedData::downcast::<web_sys::Element>) {
+        let frame = TargetFrame::measure(element, Default::default());
+        return frame.local_point(data.client_coordinates());
+    }
+
+    data.element_coordinates().cast_unit()
+}
+
+/// Recognizes two taps of a single pointer in quick succession, and calls back
+/// with the local coordinates of the second one.
+struct DoubleTap {
+    on_double_tap: Box<dyn FnMut(LocalPoint)>,
+    frames: FrameSource,
+    /// The pointer that is down, where it went down and the frame of the
+    /// viewport at that time.
+    down: Option<(PointerId, ClientPoint, Rc<TargetFrame>)>,
+    /// The preceding tap, with the token of the timeout that forgets it.
+    last_tap: Option<(ClientPoint, u64)>,
+    timeouts: Vec<(f64, u64)>,
+    next_token: u64,
+}
+
+impl DoubleTap {
+    fn new(on_double_tap: impl FnMut(LocalPoint) + 'static) -> Self {
+        Self {
+            on_double_tap: Box::new(on_double_tap),
+            frames: FrameSource::default(),
+            down: None,
+            last_tap: None,
+            timeouts: Vec::new(),
+            next_token: 0,
+        }
+    }
+
+    fn is_down(&self, event: &Event<PointerData>) -> bool {
+        matches!(self.down, Some((id, ..)) if id.is_equal_i32(event.pointer_id()))
+    }
+}
+
+impl PointerEventReceiver<&Event<PointerData>> for DoubleTap {
+    fn pointer_over(&mut self, _: &Event<PointerData>) {}
+
+    fn pointer_enter(&mut self, _: &Event<PointerData>) {}
+
+    fn pointer_down(&mut self, event: &Event<PointerData>) {
+        self.down = match self.down {
+            // A second pointer makes it a pinch.
+            Some(_) => {
+                self.last_tap = None;
+                None
+            }
+            None => Some((
+                PointerId::from(event.pointer_id()),
+                event.client_coordinates(),
+                self.frames.measure(&event.data()),
+            )),
+        };
+    }
+
+    fn pointer_move(&mut self, event: &Event<PointerData>) {
+        if let Some((_, start, _)) = self.down.as_ref().filter(|_| self.is_down(event)) {
+            if (event.client_coordinates() - *start).length() > TAP_SLOP {
+                self.down = None;
+                self.last_tap = None;
+            }
+        }
+    }
+
+    fn pointer_up(&mut self, event: &Event<PointerData>) {
+        let pointer_id = event.pointer_id();
+        let Some((_, _, frame)) = self.down.take_if(|(id, ..)| id.is_equal_i32(pointer_id)) else {
+            return;
+        };
+        let point = event.client_coordinates();
+        match self.last_tap.take() {
+            Some((last, _)) if (point - last).length() <= DOUBLE_TAP_DISTANCE => {
+                (self.on_double_tap)(frame.local_point(point));
+            }
+            _ => {
+                self.next_token += 1;
+                self.timeouts.push((DOUBLE_TAP_INTERVAL, self.next_token));
+                self.last_tap = Some((point, self.next_token));
+            }
+        }
+    }
+
+    fn pointer_cancel(&mut self, _: &Event<PointerData>) {
+        self.down = None;
+        self.last_tap = None;
+    }
+
+    fn pointer_out(&mut self, _: &Event<PointerData>) {}
+
+    fn pointer_leave(&mut self, _: &Event<PointerData>) {}
+}
+
+impl GestureRecognizer for DoubleTap {
+    fn attach_frames(&mut self, frames: FrameSource) {
+        self.frames = frames;
+    }
+
+    fn take_timeouts(&mut self) -> Vec<(f64, u64)> {
+        std::mem::take(&mut self.timeouts)
+    }
+
+    fn timeout(&mut self, token: u64) {
+        if matches!(self.last_tap, Some((_, last)) if last == token) {
+            self.last_tap = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use dioxus::html::geometry::{
+        euclid::{Rect, Size2D},
+        ClientPoint,
+    };
+
+    use crate::{
+        state::{
+            frame::{LocalPoint, LocalVector},
+            gestures::pinch::Pinch,
+        },
+        testing::{GestureHarness, PointerSequence, SyntheticPointer},
+        use_gestures::Gestures,
+        viewport::{DoubleTap, PanZoom, PanZoomState},
+    };
+
+    fn state(config: PanZoom) -> PanZoomState {
+        let mut state = PanZoomState::new(config);
+        state.viewport_size = Size2D::new(400.0, 300.0);
+        state
+    }
+
+    fn tap(x: f64, y: f64) -> PointerSequence {
+        let pointer = SyntheticPointer::touch(1).at(x, y).element_at(x, y);
+        PointerSequence::default().down(pointer).up(1)
+    }
+
+    #[test]
+    fn content_is_kept_in_view() {
+        let mut state = state(PanZoom::default().content_size(800.0, 600.0));
+        state.pan_by(LocalVector::new(50.0, -1000.0));
+        assert_eq!(state.transform.translation(), LocalVector::new(0.0, -300.0));
+
+        // Smaller content stays within the viewport.
+        state.zoom_to(0.25, Some(LocalPoint::zero()));
+        state.pan_by(LocalVector::new(1000.0, -1000.0));
+        assert_eq!(state.transform.translation(), LocalVector::new(200.0, 0.0));
+    }
+
+    #[test]
+    fn fit_centers_the_content() {
+        let mut state = state(PanZoom::default().content_size(1600.0, 600.0));
+        state.fit();
+        assert_eq!(state.zoom(), 0.25);
+        assert_eq!(state.transform.translation(), LocalVector::new(0.0, 75.0));
+    }
+
+    #[test]
+    fn zoom_is_limited() {
+        let mut state = state(PanZoom::default().min_zoom(0.5).max_zoom(2.0));
+        state.zoom_to(4.0, None);
+        assert_eq!(state.zoom(), 2.0);
+        // The center of the viewport stays in place.
+        assert_eq!(
+            state
+                .transform
+                .transform
+                .transform_point(LocalPoint::new(200.0, 150.0)),
+            LocalPoint::new(200.0, 150.0),
+        );
+        state.wheel(LocalPoint::zero(), 10_000.0);
+        assert_eq!(state.zoom(), 0.5);
+    }
+
+    #[test]
+    fn swapped_zoom_bounds_do_not_panic() {
+        let mut state = state(
+            PanZoom::default()
+                .min_zoom(5.0)
+                .max_zoom(2.0)
+                .content_size(1600.0, 600.0),
+        );
+        state.fit();
+        assert_eq!(state.zoom(), 2.0);
+        state.double_tap(LocalPoint::zero());
+        state.zoom_to(10.0, None);
+        assert_eq!(state.zoom(), 5.0);
+    }
+
+    #[test]
+    fn double_tap_zooms_in_then_fits() {
+        let mut state = state(PanZoom::default().max_zoom(4.0).content_size(400.0, 300.0));
+        state.double_tap(LocalPoint::new(100.0, 100.0));
+        state.double_tap(LocalPoint::new(100.0, 100.0));
+        assert_eq!(state.zoom(), 4.0);
+        state.double_tap(LocalPoint::new(100.0, 100.0));
+        assert_eq!(state.zoom(), 1.0);
+        assert_eq!(state.transform.translation(), LocalVector::zero());
+    }
+
+    #[test]
+    fn double_tap_is_recognized() {
+        let taps = Rc::new(RefCell::new(Vec::new()));
+        let mut harness = GestureHarness::new(Gestures::default().recognizer(
+            "double_tap",
+            DoubleTap::new({
+                let taps = Rc::clone(&taps);
+                move |point| taps.borrow_mut().push(point)
+            }),
+        ));
+
+        harness.play(tap(10.0, 10.0).then(tap(20.0, 15.0)));
+        assert_eq!(*taps.borrow(), [LocalPoint::new(20.0, 15.0)]);
+
+        // Taps too far apart, or a drag in between, are not a double tap.
+        harness.play(tap(10.0, 10.0).then(tap(100.0, 10.0)));
+        harness.play(PointerSequence::drag(
+            SyntheticPointer::touch(1).at(100.0, 10.0),
+            (50.0, 0.0),
+            2,
+        ));
+        harness.play(tap(100.0, 10.0));
+        assert_eq!(taps.borrow().len(), 1);
+    }
+
+    #[test]
+    fn double_tap_expires() {
+        let taps = Rc::new(RefCell::new(Vec::new()));
+        let mut harness = GestureHarness::new(Gestures::default().recognizer(
+            "double_tap",
+            DoubleTap::new({
+                let taps = Rc::clone(&taps);
+                move |point| taps.borrow_mut().push(point)
+            }),
+        ));
+
+        harness
+            .play(tap(10.0, 10.0))
+            .advance(400.0)
+            .play(tap(10.0, 10.0));
+        assert!(taps.borrow().is_empty());
+        harness.advance(200.0).play(tap(10.0, 10.0));
+        assert_eq!(taps.borrow().len(), 1);
+    }
+
+    /// Events fired by a child of the viewport, whose element coordinates are
+    /// relative to the child rather than the viewport.
+    fn child_pointer(pointer_id: i32, x: f64, y: f64) -> SyntheticPointer {
+        SyntheticPointer::touch(pointer_id)
+            .primary(pointer_id == 1)
+            .at(x, y)
+            .element_at(x - 150.0, y - 120.0)
+    }
+
+    #[test]
+    fn focal_points_are_relative_to_the_viewport() {
+        let viewport = Rc::new(RefCell::new(state(PanZoom::default())));
+        let mut harness = GestureHarness::new(
+            Gestures::default()
+                .pinch(Pinch::default().has_started(|_, _| true).on_update({
+                    let viewport = Rc::clone(&viewport);
+                    move |data| viewport.borrow_mut().apply_pinch(&data)
+                }))
+                .recognizer(
+                    "double_tap",
+                    DoubleTap::new({
+                        let viewport = Rc::clone(&viewport);
+                        move |point| viewport.borrow_mut().double_tap(point)
+                    }),
+                ),
+        );
+        harness.target_rect(Rect::new(
+            ClientPoint::new(100.0, 50.0),
+            Size2D::new(400.0, 300.0),
+        ));
+
+        // A pinch about the local point (100, 100).
+        harness.play(
+            PointerSequence::default()
+                .down(child_pointer(1, 175.0, 150.0))
+                .down(child_pointer(2, 225.0, 150.0))
+                .move_by_steps(1, (125.0, 150.0), 1)
+                .move_by_steps(2, (275.0, 150.0), 1)
+                .up(1)
+                .up(2),
+        );
+        let pivot = LocalPoint::new(100.0, 100.0);
+        let state = *viewport.borrow();
+        assert!((state.zoom() - 3.0).abs() < 1e-9);
+        assert!((state.transform.transform.transform_point(pivot) - pivot).length() < 1e-9);
+
+        // A double tap at the local point (50, 40).
+        viewport.borrow_mut().reset();
+        let child_tap = || {
+            PointerSequence::default()
+                .down(child_pointer(1, 150.0, 90.0))
+                .up(1)
+        };
+        harness.play(child_tap().then(child_tap()));
+        let pivot = LocalPoint::new(50.0, 40.0);
+        let state = *viewport.borrow();
+        assert_eq!(state.zoom(), 2.0);
+        assert_eq!(state.transform.transform.transform_point(pivot), pivot);
+    }
+}